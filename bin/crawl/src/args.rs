@@ -1,4 +1,4 @@
-use booru::board::Board;
+use booru::board::danbooru::Danbooru;
 use clap::{Args, Parser, ValueEnum};
 
 #[derive(Parser, Debug, Clone)]
@@ -16,10 +16,33 @@ pub struct Cli {
     #[command(flatten)]
     pub output: Output,
 
-    #[arg(long, env = "DANBOORU_USERNAME", hide_env_values = true)]
-    pub username: String,
-    #[arg(long, env = "DANBOORU_API_KEY", hide_env_values = true)]
-    pub api_key: String,
+    /// Username, omit for anonymous access
+    #[arg(
+        long,
+        env = "DANBOORU_USERNAME",
+        hide_env_values = true,
+        requires = "api_key"
+    )]
+    pub username: Option<String>,
+    #[arg(
+        long,
+        env = "DANBOORU_API_KEY",
+        hide_env_values = true,
+        requires = "username"
+    )]
+    pub api_key: Option<String>,
+
+    /// Base url of the board, e.g. a mirror or a local mock server
+    #[arg(long, env = "BOORU_BASE_URL")]
+    pub base_url: Option<String>,
+
+    /// Record every response into the directory
+    #[arg(long, conflicts_with = "replay")]
+    pub record: Option<String>,
+
+    /// Replay responses recorded with --record instead of accessing the board
+    #[arg(long)]
+    pub replay: Option<String>,
 }
 
 #[derive(Parser, Debug, ValueEnum, Clone)]
@@ -29,10 +52,10 @@ pub enum Domain {
 }
 
 impl Domain {
-    pub fn board(&self) -> Board {
+    pub fn board(&self) -> Danbooru {
         match self {
-            Domain::Danbooru => Board::Danbooru,
-            Domain::Safebooru => Board::Safebooru,
+            Domain::Danbooru => Danbooru::Danbooru,
+            Domain::Safebooru => Danbooru::Safebooru,
         }
     }
 }
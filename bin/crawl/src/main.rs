@@ -2,9 +2,9 @@ mod args;
 
 use anyhow::Result;
 use args::Cli;
-use booru::board::danbooru::{response, search, Endpoint, FileExt, Query};
-use booru::board::{danbooru, BoardQuery, BoardSearchTagsBuilder};
-use booru::client::{Auth, Client};
+use booru::board::danbooru::{response, search, Endpoint, Query};
+use booru::board::{danbooru, BoardQuery, BoardSearchTagsBuilder, Page};
+use booru::client::{Auth, Cassette, Client, Quota, RateLimiter, RequestKind};
 use clap::Parser;
 use futures::stream::{self, StreamExt};
 use indicatif::{ProgressBar, ProgressStyle};
@@ -18,28 +18,53 @@ use tokio::sync::Mutex;
 const PBAR_TEMPLATE: &str =
     "{spinner:.green} [{elapsed_precise}] {bar:50.cyan/blue} {pos:>7}/{len:7} ({eta_precise}) {msg}";
 
-fn build_query(id_from: u32, id_to: u32, tags: &str) -> Query {
+fn build_search_tags(tags: &str) -> danbooru::SearchTagsBuilder {
     let mut builder = danbooru::SearchTagsBuilder::new();
     builder.add_tag(tags);
 
-    builder.ids(vec![search::Id::InEx {
-        min: id_from,
-        max: id_to,
-    }]);
-    builder.order(search::Order::Id(search::OrderBy::Asc));
-
-    // println!("query: {}", builder.build());
+    builder
+}
 
-    let mut query = Query::posts(&builder.build());
+fn build_query(tags: &str) -> Query {
+    let mut query = Query::posts(build_search_tags(tags).build().trim());
     query.limit(200);
 
     query
 }
 
+/// count posts to be crawled in the id range (id_start <= id < id_end)
+async fn count_posts(
+    client: &Client,
+    tags: &str,
+    id_start: u32,
+    id_end: u32,
+) -> Result<Option<i64>> {
+    let mut builder = build_search_tags(tags);
+    builder.ids(vec![search::Id::MinMax {
+        min: id_start,
+        max: id_end.saturating_sub(1),
+    }]);
+
+    Ok(client.count(&builder).await?)
+}
+
 fn compose_url(client: &Client, query: Query) -> Result<Url> {
     Ok(client.compose(Endpoint::Posts, query)?)
 }
 
+/// fetch posts whose id is id_head or greater, sorted by id
+async fn fetch_posts_from(client: &Client, query: &Query, id_head: u32) -> Result<response::Posts> {
+    // without spending a search tag for id range
+    let mut query = query.clone();
+    query.page(Page::After(i64::from(id_head) - 1));
+
+    let url = compose_url(client, query)?;
+    let mut posts = client.fetch::<response::Posts>(url, Method::GET).await?;
+    posts.sort_by_key(|post| post.id);
+
+    Ok(posts)
+}
+
 fn get_output_file_path<P: AsRef<Path>>(base_dir: P, name: &str) -> String {
     base_dir
         .as_ref()
@@ -52,14 +77,31 @@ fn get_output_file_path<P: AsRef<Path>>(base_dir: P, name: &str) -> String {
 async fn main() -> Result<()> {
     let args = Cli::parse();
 
-    let auth = Auth::new(&args.username, &args.api_key);
-    let client = Client::new(args.domain.board(), auth)?;
+    let auth = args
+        .username
+        .zip(args.api_key)
+        .map(|(username, api_key)| Auth::new(&username, &api_key));
+    let max_requests_per_second = args.output.max_requests_per_second;
+    let mut client_builder = Client::builder(args.domain.board())
+        .auth(auth)
+        .rate_limiter(
+            RateLimiter::danbooru()
+                .read_quota(Some(Quota::per_second(max_requests_per_second as f64))),
+        );
+    if let Some(base_url) = &args.base_url {
+        client_builder = client_builder.base_url(base_url);
+    }
+    if let Some(dir) = &args.record {
+        client_builder = client_builder.cassette(Cassette::record(dir));
+    } else if let Some(dir) = &args.replay {
+        client_builder = client_builder.cassette(Cassette::replay(dir));
+    }
+    let client = client_builder.build()?;
 
     let output_dir = args.output.output_path;
     let output_name = args.output.prefix.unwrap_or(args.domain.to_string());
     let write_concurrency = args.output.write_concurrency;
     let overwrite = args.output.overwrite;
-    let max_requests_per_second = args.output.max_requests_per_second;
 
     // if output dir does not exist, create it
     tokio::fs::create_dir_all(&output_dir).await?;
@@ -107,32 +149,39 @@ async fn main() -> Result<()> {
         .await
         .expect("Failed to open file");
     let shared_output_file = Arc::new(Mutex::new(output_file));
-    let delay = std::time::Duration::from_secs_f64(1.0 / max_requests_per_second as f64);
-    let bar = ProgressBar::new(id_end as u64);
+    // the count is only for the progress bar, so crawl anyway if it fails
+    let bar = match count_posts(&client, &args.tags, id_start, id_end).await {
+        Ok(Some(count)) => ProgressBar::new(count as u64),
+        Ok(None) => {
+            eprintln!("warning: the board did not return the post count");
+            ProgressBar::no_length()
+        }
+        Err(e) => {
+            eprintln!("warning: failed to count posts: {}", e);
+            ProgressBar::no_length()
+        }
+    };
     bar.set_style(ProgressStyle::with_template(PBAR_TEMPLATE)?);
 
+    let query = build_query(&args.tags);
+
     let mut id_head = id_start;
-    let page_size = 200;
     while id_head < id_end {
-        let id_tail = id_head + page_size;
-        let query = build_query(id_head, id_tail, &args.tags);
-
         let mut tasks = vec![];
 
         // start crawling
-        bar.set_message(format!("{id_head}~{id_tail}"));
-
-        let mut query = query.clone();
-        query.page(1);
+        bar.set_message(format!("{id_head}~"));
 
-        let url = compose_url(&client, query)?;
-        let posts = client.fetch::<response::Posts>(url, Method::GET).await?;
+        let mut posts = fetch_posts_from(&client, &query, id_head).await?;
 
         if posts.is_empty() {
-            id_head += page_size;
-            continue;
+            // no more posts
+            break;
         }
-        let last_post_id = (&posts.last().unwrap().id).clone() as u32;
+        let last_post_id = posts.last().unwrap().id as u32;
+        posts.retain(|post| post.id < i64::from(id_end));
+
+        let num_posts = posts.len() as u64;
 
         // write out
         let cloned_output_file = Arc::clone(&shared_output_file);
@@ -154,13 +203,60 @@ async fn main() -> Result<()> {
             .collect::<Vec<_>>()
             .await;
 
-        bar.set_position(last_post_id as u64);
-
-        // delay for rate limiting
-        tokio::time::sleep(delay).await;
+        bar.inc(num_posts);
 
         id_head = last_post_id + 1;
     }
 
+    bar.finish();
+
+    let stats = client.rate_limiter().stats(RequestKind::Read);
+    println!(
+        "{} requests, waited {:?} in total for rate limiting",
+        stats.requests, stats.total_wait
+    );
+
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use booru::mock::{fixtures, MockResponse, MockServer};
+
+    #[tokio::test]
+    async fn test_fetch_posts_from() {
+        let server = MockServer::start().await;
+        server
+            .mock(Method::GET, "/posts.json")
+            .query("page", "a8100000")
+            .respond(MockResponse::json(fixtures::POSTS))
+            .mount();
+        let client = server.client();
+
+        let query = build_query("cat_ears");
+        let posts = fetch_posts_from(&client, &query, 8100001).await.unwrap();
+        let ids = posts.iter().map(|post| post.id).collect::<Vec<_>>();
+        assert_eq!(ids, vec![8100001, 8100002, 8100003]);
+
+        let requests = server.requests();
+        assert_eq!(requests[0].param("tags"), Some("cat_ears"));
+        assert_eq!(requests[0].param("limit"), Some("200"));
+    }
+
+    #[tokio::test]
+    async fn test_count_posts() {
+        let server = MockServer::start().await;
+        server
+            .mock(Method::GET, "/counts/posts.json")
+            .respond(MockResponse::json(fixtures::COUNTS))
+            .mount();
+        let client = server.client();
+
+        let count = count_posts(&client, "cat_ears", 100, 200).await.unwrap();
+        assert_eq!(count, Some(1234));
+
+        let requests = server.requests();
+        assert_eq!(requests[0].param("tags"), Some("cat_ears id:100..199"));
+    }
+}
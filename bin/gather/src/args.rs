@@ -1,4 +1,4 @@
-use booru::board::Board;
+use booru::board::danbooru::Danbooru;
 use clap::{Args, Parser, ValueEnum};
 use std::time::Duration;
 
@@ -21,11 +21,34 @@ pub struct Cli {
     #[command(flatten)]
     pub cache: Cache,
 
-    #[arg(long, env = "DANBOORU_USERNAME", hide_env_values = true)]
-    pub username: String,
+    /// Username, omit for anonymous access
+    #[arg(
+        long,
+        env = "DANBOORU_USERNAME",
+        hide_env_values = true,
+        requires = "api_key"
+    )]
+    pub username: Option<String>,
+
+    #[arg(
+        long,
+        env = "DANBOORU_API_KEY",
+        hide_env_values = true,
+        requires = "username"
+    )]
+    pub api_key: Option<String>,
+
+    /// Base url of the board, e.g. a mirror or a local mock server
+    #[arg(long, env = "BOORU_BASE_URL")]
+    pub base_url: Option<String>,
 
-    #[arg(long, env = "DANBOORU_API_KEY", hide_env_values = true)]
-    pub api_key: String,
+    /// Record every response into the directory
+    #[arg(long, conflicts_with = "replay")]
+    pub record: Option<String>,
+
+    /// Replay responses recorded with --record instead of accessing the board
+    #[arg(long)]
+    pub replay: Option<String>,
 }
 
 #[derive(Parser, Debug, ValueEnum, Clone)]
@@ -35,10 +58,10 @@ pub enum Domain {
 }
 
 impl Domain {
-    pub fn board(&self) -> Board {
+    pub fn board(&self) -> Danbooru {
         match self {
-            Domain::Danbooru => Board::Danbooru,
-            Domain::Safebooru => Board::Safebooru,
+            Domain::Danbooru => Danbooru::Danbooru,
+            Domain::Safebooru => Danbooru::Safebooru,
         }
     }
 }
@@ -85,6 +108,10 @@ pub struct Output {
     /// Save images with specific file extension
     #[arg(short = 'E', long)]
     pub file_ext: Option<FileExt>,
+
+    /// Drop tags implied by other tags of the post (e.g. animal_ears with cat_ears)
+    #[arg(long)]
+    pub drop_implied_tags: bool,
 }
 
 #[derive(Args, Debug, Clone)]
@@ -3,9 +3,10 @@ mod utils;
 
 use anyhow::{Context, Result};
 use args::{Cli, FileExt as SaveFileExt};
-use booru::board::danbooru::{response, search, Endpoint, FileExt, Query};
-use booru::board::{danbooru, BoardQuery, BoardSearchTagsBuilder};
-use booru::client::{Auth, Client};
+use booru::board::danbooru::{search, FileExt};
+use booru::board::{danbooru, BoardSearchTagsBuilder};
+use booru::client::{Auth, Cassette, Client, PageMode};
+use booru::model;
 use clap::Parser;
 use futures::stream::{self, StreamExt};
 use futures::TryStreamExt;
@@ -19,7 +20,11 @@ use tokio::io::AsyncWriteExt;
 const PBAR_TEMPLATE: &str =
     "{spinner:.green} [{elapsed_precise}] {bar:50.cyan/blue} {pos:>7}/{len:7} ({eta}) {msg}";
 
-fn build_query(tags: &str, score_min: i32, score_max: Option<i32>) -> Query {
+fn build_search_tags(
+    tags: &str,
+    score_min: i32,
+    score_max: Option<i32>,
+) -> danbooru::SearchTagsBuilder {
     let mut builder = danbooru::SearchTagsBuilder::new();
     builder.add_tag(tags);
     builder.add_tag("-is:banned");
@@ -36,14 +41,7 @@ fn build_query(tags: &str, score_min: i32, score_max: Option<i32>) -> Query {
 
     println!("query: {}", builder.build());
 
-    let mut query = Query::posts(&builder.build());
-    query.limit(200);
-
-    query
-}
-
-fn compose_url(client: &Client, query: Query) -> Result<Url> {
-    Ok(client.compose(Endpoint::Posts, query)?)
+    builder
 }
 
 fn get_image_path<P: AsRef<Path>>(base_dir: P, id: &i64, extension: &str) -> Result<String> {
@@ -86,8 +84,20 @@ async fn main() -> Result<()> {
 
     // println!("{:?}", args);
 
-    let auth = Auth::new(&args.username, &args.api_key);
-    let client = Client::new(args.domain.board(), auth)?;
+    let auth = args
+        .username
+        .zip(args.api_key)
+        .map(|(username, api_key)| Auth::new(&username, &api_key));
+    let mut client_builder = Client::builder(args.domain.board()).auth(auth);
+    if let Some(base_url) = &args.base_url {
+        client_builder = client_builder.base_url(base_url);
+    }
+    if let Some(dir) = &args.record {
+        client_builder = client_builder.cassette(Cassette::record(dir));
+    } else if let Some(dir) = &args.replay {
+        client_builder = client_builder.cassette(Cassette::replay(dir));
+    }
+    let client = client_builder.build()?;
 
     let tags = args.tags;
     let score_min = args.condition.score_min;
@@ -100,38 +110,52 @@ async fn main() -> Result<()> {
     let num_posts = args.output.num_posts;
     let file_ext = args.output.file_ext;
     let tag_template = Arc::new(args.output.tag_template);
+    let drop_implied_tags = args.output.drop_implied_tags;
 
     // let cache_dir = &args.cache.cache_path;
     // let cache_lifetime = &args.cache.lifetime();
 
     tokio::fs::create_dir_all(&output_dir.clone().as_ref()).await?;
 
-    let query = build_query(&tags, score_min, score_max);
+    let builder = build_search_tags(&tags, score_min, score_max);
+
+    // the search may match fewer posts than requested, fall back to the requested number if counting fails
+    let total_posts = match client.count(&builder).await {
+        Ok(Some(count)) => num_posts.min(u32::try_from(count).unwrap_or(u32::MAX)),
+        Ok(None) => {
+            eprintln!("warning: the board did not return the post count");
+            num_posts
+        }
+        Err(e) => {
+            eprintln!("warning: failed to count posts: {}", e);
+            num_posts
+        }
+    };
 
     let multi_bar = MultiProgress::new();
 
     // the total progress bar
-    let total_bar = multi_bar.add(ProgressBar::new(num_posts as u64));
+    let total_bar = multi_bar.add(ProgressBar::new(total_posts as u64));
     let bar_style = ProgressStyle::default_bar().template(PBAR_TEMPLATE)?;
     total_bar.set_style(bar_style.clone());
     total_bar.set_message("Total Progress");
 
     // let shared_bar = Arc::new(tokio::sync::Mutex::new(bar));
-    let tag_manager = Arc::new(utils::TagManager::new());
+    let mut tag_manager = utils::TagManager::new();
+    if drop_implied_tags {
+        println!("Fetching tag implications...");
+        let tag_graph = client.full_tag_graph().await?;
+        tag_manager = tag_manager.with_tag_graph(tag_graph);
+    }
+    let tag_manager = Arc::new(tag_manager);
 
+    let mut pages = client
+        .posts_stream(builder, PageMode::Numbered(1))
+        .map_ok(|post| model::Post::from_native(post, &client.board))
+        .try_chunks(200)
+        .map_err(|e| e.1);
     let mut page = 1;
-    loop {
-        let mut query = query.clone();
-        query.page(page);
-
-        let url = compose_url(&client, query)?;
-        let posts = client.fetch::<response::Posts>(url, Method::GET).await?;
-
-        if posts.is_empty() {
-            // no more posts
-            break;
-        }
-
+    while let Some(posts) = pages.try_next().await? {
         let rest_posts = num_posts - total_bar.position() as u32;
         let required_posts = &posts
             .into_iter()
@@ -163,7 +187,7 @@ async fn main() -> Result<()> {
 
         let bar = multi_bar.add(ProgressBar::new(required_posts.len() as u64));
         bar.set_style(bar_style.clone());
-        bar.set_message(format!("{}, page: 1", &tags));
+        bar.set_message(format!("{}, page: {}", &tags, page));
 
         // firstly download images
         let _ = bar
@@ -245,7 +269,7 @@ async fn main() -> Result<()> {
     total_bar.finish_with_message("All Done.");
     println!(
         "Downloaded {} posts from {} with tags: {}",
-        num_posts,
+        total_bar.position(),
         args.domain.to_string(),
         tags
     );
@@ -2,13 +2,15 @@ use std::collections::HashMap;
 
 use booru::{
     board::danbooru,
-    tags::{split_whitespaces, TagMatcher, TagNormalizer},
+    model::{Post, TagCategory},
+    tags::{TagGraph, TagMatcher, TagNormalizer},
 };
 
 pub struct TagManager {
     normalizer: danbooru::tags::Normalizer,
     people_matcher: danbooru::tags::Matcher,
     ooc_meta_matcher: danbooru::tags::Matcher,
+    tag_graph: Option<TagGraph>,
 }
 
 impl TagManager {
@@ -19,6 +21,20 @@ impl TagManager {
             ooc_meta_matcher: danbooru::tags::Matcher::new(
                 danbooru::tags::OUT_OF_CONTEXT_META_TAG_PARTS.to_vec(),
             ),
+            tag_graph: None,
+        }
+    }
+
+    /// drop tags implied by other tags of the same category
+    pub fn with_tag_graph(mut self, tag_graph: TagGraph) -> Self {
+        self.tag_graph = Some(tag_graph);
+        self
+    }
+
+    pub fn remove_implied(&self, tags: Vec<String>) -> Vec<String> {
+        match &self.tag_graph {
+            Some(graph) => graph.remove_implied(tags),
+            None => tags,
         }
     }
 
@@ -44,12 +60,16 @@ impl TagManager {
         result
     }
 
-    pub fn format_template(&self, template: &str, post: &danbooru::response::Post) -> String {
-        let general_tags = split_whitespaces(&post.tag_string_general);
-        let character_tags = split_whitespaces(&post.tag_string_character);
-        let copyright_tags = split_whitespaces(&post.tag_string_copyright);
-        let artist_tags = split_whitespaces(&post.tag_string_artist);
-        let meta_tags = split_whitespaces(&post.tag_string_meta);
+    pub fn format_template(&self, template: &str, post: &Post) -> String {
+        let tags = |category| post.tags(category).to_vec();
+
+        // boards without tag categories only fill {general}
+        let general_tags = [tags(TagCategory::General), tags(TagCategory::Unknown)].concat();
+        let general_tags = self.remove_implied(general_tags);
+        let character_tags = self.remove_implied(tags(TagCategory::Character));
+        let copyright_tags = self.remove_implied(tags(TagCategory::Copyright));
+        let artist_tags = tags(TagCategory::Artist);
+        let meta_tags = tags(TagCategory::Meta);
 
         let (people_tags, general_tags) = self.people_matcher.classify_has(general_tags);
         let (_ooc_meta_tags, meta_tags) = self.ooc_meta_matcher.classify_any_in(meta_tags);
@@ -76,6 +96,9 @@ impl TagManager {
 #[cfg(test)]
 mod test {
     use super::*;
+    use booru::board::BoardResponse;
+    use booru::mock::fixtures;
+    use booru::tags::split_whitespaces;
 
     #[test]
     fn test_tag_manager_replace_tags() {
@@ -135,4 +158,39 @@ mod test {
 
         assert_eq!(result, "original, 1girl, cat ears, example (artist)");
     }
+
+    #[test]
+    fn test_tag_manager_remove_implied() {
+        let mut graph = TagGraph::new();
+        graph.add_implication("cat_ears", "animal_ears");
+        let manager = TagManager::new().with_tag_graph(graph);
+
+        let general = split_whitespaces("1girl animal_ears cat_ears");
+        assert_eq!(manager.remove_implied(general), vec!["1girl", "cat_ears"]);
+
+        let manager = TagManager::new();
+        let general = split_whitespaces("1girl animal_ears cat_ears");
+        assert_eq!(manager.remove_implied(general).len(), 3);
+    }
+
+    #[test]
+    fn test_tag_manager_format_template() {
+        let manager = TagManager::new();
+
+        let post = danbooru::response::Post::from_str(fixtures::POST).unwrap();
+        assert_eq!(
+            manager.format_template(
+                "{people}, {character}, {copyright}, {general}, {artist}, {meta}",
+                &Post::from(post)
+            ),
+            "1girl, hatsune miku, vocaloid, animal ears, blue eyes, cat ears, solo, example (artist), highres"
+        );
+
+        let mut post = Post::from(danbooru::response::Post::from_str(fixtures::POST).unwrap());
+        post.tags = [(TagCategory::Unknown, split_whitespaces("1girl cat_ears"))].into();
+        assert_eq!(
+            manager.format_template("{people}, {general}", &post),
+            "1girl, cat ears"
+        );
+    }
 }
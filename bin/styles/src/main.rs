@@ -1,8 +1,133 @@
 use anyhow::Result;
+use booru::board::danbooru::{response, Query};
+use booru::board::{BoardQuery, Page};
+use booru::client::{Auth, Client};
+use clap::Parser;
+use indicatif::{ProgressBar, ProgressStyle};
+use serde::Serialize;
+use std::path::PathBuf;
+use tokio::io::AsyncWriteExt;
+
+const PBAR_TEMPLATE: &str = "{spinner:.green} [{elapsed_precise}] {pos:>7} artists {msg}";
+
+#[derive(Debug, Parser)]
+#[command(version, about = "Build a catalog of artists", long_about = None)]
+struct Args {
+    /// Username, omit for anonymous access
+    #[arg(
+        long,
+        env = "DANBOORU_USERNAME",
+        hide_env_values = true,
+        requires = "api_key"
+    )]
+    pub username: Option<String>,
+    #[arg(
+        long,
+        env = "DANBOORU_API_KEY",
+        hide_env_values = true,
+        requires = "username"
+    )]
+    pub api_key: Option<String>,
+
+    /// Artist name, group name or other name to match, `*` matches any characters
+    #[arg(long, default_value = "*")]
+    pub name_matches: String,
+
+    #[arg(short, long, default_value = "./output/artists.jsonl")]
+    pub output: PathBuf,
+
+    /// Max number of artists to save
+    #[arg(long)]
+    pub max_artists: Option<usize>,
+
+    /// Also save banned artists
+    #[arg(long)]
+    pub include_banned: bool,
+}
+
+/// a line of the catalog
+#[derive(Debug, Serialize)]
+struct ArtistEntry {
+    id: i64,
+    name: String,
+    group_name: String,
+    other_names: Vec<String>,
+    urls: Vec<String>,
+    is_banned: bool,
+}
+
+impl From<&response::Artist> for ArtistEntry {
+    fn from(artist: &response::Artist) -> Self {
+        ArtistEntry {
+            id: artist.id,
+            name: artist.name.clone(),
+            group_name: artist.group_name.clone(),
+            other_names: artist.other_names.clone(),
+            urls: artist.active_urls().map(String::from).collect(),
+            is_banned: artist.is_banned,
+        }
+    }
+}
+
+fn build_query(name_matches: &str, page: Page) -> Query {
+    let mut query = Query::artists(name_matches);
+    query.insert("search[is_deleted]", false);
+    query.limit(1000);
+    query.page(page);
+    query
+}
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    println!("Hello, world!");
+    let args = Args::parse();
+
+    let auth = args
+        .username
+        .zip(args.api_key)
+        .map(|(username, api_key)| Auth::new(&username, &api_key));
+    let client = Client::builder(booru::board::danbooru::Danbooru::Danbooru)
+        .auth(auth)
+        .build()?;
+
+    if let Some(parent) = args.output.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    let mut file = tokio::fs::File::create(&args.output).await?;
+
+    let bar = ProgressBar::new_spinner().with_style(ProgressStyle::with_template(PBAR_TEMPLATE)?);
+
+    // newest artists first
+    let mut page = Page::Number(1);
+    let mut saved = 0;
+    'pages: loop {
+        let artists = client
+            .artists(build_query(&args.name_matches, page))
+            .await?;
+        let Some(min_id) = artists.iter().map(|a| a.id).min() else {
+            break;
+        };
+        page = Page::Before(min_id);
+        bar.set_message(format!("~{}", min_id));
+
+        for artist in artists
+            .iter()
+            .filter(|a| args.include_banned || !a.is_banned)
+        {
+            if args.max_artists.is_some_and(|max| saved >= max) {
+                break 'pages;
+            }
+
+            let line = serde_json::to_string(&ArtistEntry::from(artist))?;
+            file.write_all(line.as_bytes()).await?;
+            file.write_all(b"\n").await?;
+
+            saved += 1;
+            bar.inc(1);
+        }
+    }
+    file.flush().await?;
+
+    bar.finish_with_message(format!("saved to {}", args.output.display()));
 
     Ok(())
 }
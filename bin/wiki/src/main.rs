@@ -1,8 +1,7 @@
 use anyhow::{bail, Context, Result};
 use booru::board::danbooru::response::WikiPage;
 use booru::board::danbooru::{response, Endpoint, Query};
-use booru::board::BoardResponse;
-use booru::client::{Auth, Client};
+use booru::client::{Auth, Client, Quota, RateLimiter};
 use clap::Parser;
 use futures::stream::StreamExt;
 use futures::TryStreamExt;
@@ -11,7 +10,7 @@ use indicatif::{MultiProgress, ParallelProgressIterator, ProgressBar, ProgressSt
 use parquet::file::reader::{FileReader, SerializedFileReader};
 use parquet::record::Field;
 use rayon::iter::{IntoParallelIterator, ParallelBridge, ParallelIterator};
-use reqwest::{Method, Url};
+use reqwest::Method;
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
 use std::fs::File;
@@ -19,7 +18,6 @@ use std::io::BufRead;
 use std::path::PathBuf;
 use std::sync::{Arc, RwLock};
 use tokio::io::AsyncWriteExt;
-use tokio::time::sleep;
 
 use hf::from_hub;
 
@@ -28,10 +26,21 @@ const PBAR_TEMPLATE: &str =
 
 #[derive(Debug, Parser)]
 struct Args {
-    #[arg(long, env = "DANBOORU_USERNAME", hide_env_values = true)]
-    pub username: String,
-    #[arg(long, env = "DANBOORU_API_KEY", hide_env_values = true)]
-    pub api_key: String,
+    /// Username, omit for anonymous access
+    #[arg(
+        long,
+        env = "DANBOORU_USERNAME",
+        hide_env_values = true,
+        requires = "api_key"
+    )]
+    pub username: Option<String>,
+    #[arg(
+        long,
+        env = "DANBOORU_API_KEY",
+        hide_env_values = true,
+        requires = "username"
+    )]
+    pub api_key: Option<String>,
 
     #[arg(long, default_value = "isek-ai/danbooru-tags-2024")]
     pub tags_ds: String,
@@ -49,56 +58,14 @@ struct Args {
     pub limit_per_sec: usize,
 }
 
-#[derive(thiserror::Error, Debug)]
-pub enum TagWikiError {
-    #[error("the data for key `{0}` is not available")]
-    NotFound(String),
-    #[error("too many requests: {0}")]
-    TooManyRequests(String),
-    #[error("bad request: {0}")]
-    BadRequest(String),
-    #[error("failed to decode text")]
-    FailedToDecode,
-    #[error("failed to parse json")]
-    FailedToParseJSON(String),
-    #[error(transparent)]
-    Unknown(#[from] anyhow::Error),
-}
-
-fn compose_url(client: &Client, title: &str) -> Result<Url> {
-    Ok(client.compose(Endpoint::WikiPages(title.to_string()), Query::new())?)
-}
-
 fn with_underscore(tag: &str) -> String {
     tag.replace(" ", "_")
 }
 
-async fn fetch_wiki_page(client: &Client, title: &str) -> Result<response::WikiPage, TagWikiError> {
+async fn fetch_wiki_page(client: &Client, title: &str) -> Result<response::WikiPage, booru::Error> {
     let title = with_underscore(title);
-    let url = compose_url(client, &title).map_err(|e| TagWikiError::Unknown(e))?;
-    let res = client
-        .fetch_raw(url, Method::GET)
-        .await
-        .map_err(|e| TagWikiError::Unknown(e))?;
-
-    match res.status() {
-        reqwest::StatusCode::NOT_FOUND => {
-            return Err(TagWikiError::NotFound(title.to_string()).into());
-        }
-        reqwest::StatusCode::TOO_MANY_REQUESTS => {
-            return Err(TagWikiError::TooManyRequests(title.to_string()).into());
-        }
-        reqwest::StatusCode::BAD_REQUEST => {
-            return Err(TagWikiError::BadRequest(title.to_string()).into());
-        }
-        _ => {}
-    }
-
-    let text = res.text().await.map_err(|_| TagWikiError::FailedToDecode)?;
-    let wiki = response::WikiPage::from_str(&text)
-        .map_err(|_| TagWikiError::FailedToParseJSON(title.clone()))?;
-
-    Ok(wiki)
+    let url = client.compose(Endpoint::WikiPages(title), Query::new())?;
+    client.fetch::<response::WikiPage>(url, Method::GET).await
 }
 
 fn load_tags_ds(repo_name: &str) -> Result<Vec<SerializedFileReader<File>>> {
@@ -133,7 +100,10 @@ pub struct WikiPageWithCategory {
 async fn main() -> Result<()> {
     let args = Args::parse();
 
-    let auth = Auth::new(&args.username, &args.api_key);
+    let auth = args
+        .username
+        .zip(args.api_key)
+        .map(|(username, api_key)| Auth::new(&username, &api_key));
 
     let ds = load_tags_ds(&args.tags_ds)?;
 
@@ -363,10 +333,15 @@ async fn main() -> Result<()> {
             .open(&args.not_founds)
             .await?,
     ));
-    let client = Arc::new(Client::new(booru::board::Board::Safebooru, auth)?);
-    let delay_time = Arc::new(std::time::Duration::from_secs_f64(
-        1.0 / args.limit_per_sec as f64,
-    ));
+    let client = Arc::new(
+        Client::builder(booru::board::danbooru::Danbooru::Safebooru)
+            .auth(auth)
+            .rate_limiter(
+                RateLimiter::danbooru()
+                    .read_quota(Some(Quota::per_second(args.limit_per_sec as f64))),
+            )
+            .build()?,
+    );
     let tag_to_category = Arc::new(tag_to_category);
 
     let _ = pbar
@@ -374,19 +349,18 @@ async fn main() -> Result<()> {
         .map(|tag| {
             let client = client.clone();
             async move {
-                let wiki = fetch_wiki_page(&client, &tag.clone()).await?;
-                Ok((tag, wiki))
+                let wiki = fetch_wiki_page(&client, &tag).await;
+                (tag, wiki)
             }
         })
         .buffer_unordered(num_connections)
-        .map(|pair: Result<(String, WikiPage), TagWikiError>| {
+        .map(|(tag, wiki): (String, Result<WikiPage, booru::Error>)| {
             let file = output_file.clone();
             let not_founds = not_founds.clone();
-            let delay_time = delay_time.clone();
             let tag_to_category = tag_to_category.clone();
             async move {
-                match pair {
-                    Result::Ok((tag, wiki)) => {
+                match wiki {
+                    Result::Ok(wiki) => {
                         let mut file = file.lock().await;
                         let wiki_str = serde_json::to_string(&wiki)?;
                         let wiki: response::WikiPage = serde_json::from_str(&wiki_str)?;
@@ -411,31 +385,23 @@ async fn main() -> Result<()> {
                     Result::Err(e) => {
                         eprintln!("error: {:?}", e);
                         match e {
-                            TagWikiError::NotFound(tag) => {
+                            booru::Error::NotFound(_) => {
                                 let mut file = not_founds.lock().await;
                                 file.write_all(tag.as_bytes()).await?;
                                 file.write_all(b"\n").await?;
                             }
-                            TagWikiError::TooManyRequests(msg) => {
-                                eprintln!("too many requests: {}", msg);
-                                sleep(*delay_time).await;
-                            }
-                            TagWikiError::BadRequest(msg) => {
-                                eprintln!("bad request: {}", msg);
-                            }
-                            TagWikiError::FailedToParseJSON(text) => {
-                                bail!("failed to parse json: {}", text);
+                            booru::Error::TooManyRequests(e) => {
+                                eprintln!("too many requests: {}", e);
                             }
-                            TagWikiError::FailedToDecode => {
-                                bail!("failed to decode text");
+                            booru::Error::ClientError(e) => {
+                                eprintln!("bad request: {}", e);
                             }
-                            TagWikiError::Unknown(e) => {
+                            e => {
                                 bail!(e);
                             }
                         }
                     }
                 }
-                sleep(*delay_time).await;
                 anyhow::Result::<_>::Ok(())
             }
         })
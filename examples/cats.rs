@@ -1,6 +1,5 @@
 use anyhow::Result;
-use booru::board::danbooru;
-use booru::board::{BoardQuery, BoardSearchTagsBuilder};
+use booru::board::{danbooru, Board, BoardQuery, BoardSearchTagsBuilder};
 use booru::client::{Auth, Client};
 use clap::{Args, Parser, ValueEnum};
 use imgcatr::ops;
@@ -12,18 +11,31 @@ use std::str::FromStr;
 #[derive(Parser, Debug, Clone)]
 #[command(version, about, long_about = None)]
 pub struct Args {
-    #[arg(long, env = "DANBOORU_USERNAME", hide_env_values = true)]
-    pub username: String,
-    #[arg(long, env = "DANBOORU_API_KEY", hide_env_values = true)]
-    pub api_key: String,
+    #[arg(
+        long,
+        env = "DANBOORU_USERNAME",
+        hide_env_values = true,
+        requires = "api_key"
+    )]
+    pub username: Option<String>,
+    #[arg(
+        long,
+        env = "DANBOORU_API_KEY",
+        hide_env_values = true,
+        requires = "username"
+    )]
+    pub api_key: Option<String>,
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
     let args = Args::parse();
 
-    let auth = Auth::new(&args.username, &args.api_key);
-    let client = Client::danbooru(auth)?;
+    let auth = args
+        .username
+        .zip(args.api_key)
+        .map(|(username, api_key)| Auth::new(&username, &api_key));
+    let client = Client::builder(Board::Danbooru).auth(auth).build()?;
 
     // search "tags" builder
     let mut builder = danbooru::SearchTagsBuilder::new();
@@ -1,24 +1,20 @@
 pub mod danbooru;
+pub mod e621;
+pub mod e926;
+pub mod gelbooru;
+pub mod konachan;
+pub mod moebooru;
 pub mod safebooru;
+pub mod search;
+pub mod yandere;
 
-use anyhow::Result;
+use crate::client::{Client, RateLimiter, DEFAULT_USER_AGENT};
+use crate::error::{Error, Result};
+use crate::model;
+use reqwest::Method;
 use std::collections::HashMap;
-
-/// Supported WebSite enum
-#[derive(Debug, Clone)]
-pub enum Board {
-    Danbooru,
-    Safebooru,
-}
-
-impl Board {
-    pub fn host(&self) -> &str {
-        match self {
-            Board::Danbooru => danbooru::HOST,
-            Board::Safebooru => safebooru::HOST,
-        }
-    }
-}
+use std::fmt;
+use std::future::Future;
 
 /// Response object post from board
 pub trait BoardResponse {
@@ -31,15 +27,55 @@ pub trait BoardResponse {
 pub trait BoardEndpoint {
     fn path(&self) -> String;
 
+    /// percent-encode a path segment
     fn urlencode(&self, s: &str) -> String {
-        url::form_urlencoded::byte_serialize(s.as_bytes()).collect::<String>()
+        // byte_serialize encodes "+" as "%2B", so remaining "+" are spaces
+        url::form_urlencoded::byte_serialize(s.as_bytes())
+            .collect::<String>()
+            .replace('+', "%20")
+    }
+}
+
+/// "page" query parameter
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Page {
+    /// page number starting from 1
+    Number(i64),
+    /// posts whose id is lower than the id (`b<id>`), in descending order on moebooru
+    /// which replaces the `order:` of the search
+    Before(i64),
+    /// posts whose id is greater than the id (`a<id>`), in ascending order on moebooru
+    /// which replaces the `order:` of the search
+    After(i64),
+}
+
+impl fmt::Display for Page {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Page::Number(page) => write!(f, "{}", page),
+            Page::Before(id) => write!(f, "b{}", id),
+            Page::After(id) => write!(f, "a{}", id),
+        }
+    }
+}
+
+impl From<i64> for Page {
+    fn from(page: i64) -> Self {
+        Page::Number(page)
     }
 }
 
 /// Request query
 pub trait BoardQuery {
-    /// convert to query string
-    fn to_string(&self) -> String;
+    /// query parameters in insertion order
+    fn pairs(&self) -> Vec<(String, String)>;
+
+    /// convert to urlencoded query string
+    fn to_string(&self) -> String {
+        url::form_urlencoded::Serializer::new(String::new())
+            .extend_pairs(self.pairs())
+            .finish()
+    }
 
     /// insert query parameter
     fn insert<T: ToString, K: ToString>(&mut self, key: T, value: K);
@@ -50,8 +86,8 @@ pub trait BoardQuery {
     }
 
     /// insert "page" query parameter
-    fn page(&mut self, page: i64) {
-        self.insert("page", page);
+    fn page<P: Into<Page>>(&mut self, page: P) {
+        self.insert("page", page.into());
     }
 }
 
@@ -78,3 +114,103 @@ pub trait BoardSearchTagsBuilder {
     /// convert to query string
     fn build(&self) -> String;
 }
+
+/// API of a board engine, tying its endpoints, queries, search tags builder and posts together
+///
+/// the engine is also the value of the board running it (e.g. `Moebooru::Yandere`), so
+/// implement it for a type outside this crate to use `Client<B>` with other boards
+pub trait BoardApi: fmt::Debug + Clone + Send + Sync {
+    type Endpoint: BoardEndpoint;
+    type Query: BoardQuery;
+    type SearchBuilder: BoardSearchTagsBuilder + Send + Sync;
+    type Post: model::NativePost<Board = Self> + Send;
+    /// response of the posts endpoint
+    type Posts: BoardResponse + IntoIterator<Item = Self::Post> + Send;
+
+    /// max number of posts per page
+    const MAX_LIMIT: i64;
+
+    /// names of the query parameters carrying the username (or user id) and the api key
+    const AUTH_PARAMS: (&'static str, &'static str);
+
+    /// whether the board accepts credentials in the `Authorization` header
+    const BASIC_AUTH: bool;
+
+    /// default host of the board
+    fn host(&self) -> &str;
+
+    /// name of the board, identifying it in normalized posts (e.g. `safebooru`)
+    fn name(&self) -> &str;
+
+    /// User-Agent header value used unless another one is set
+    fn user_agent(&self, _username: Option<&str>) -> String {
+        DEFAULT_USER_AGENT.to_string()
+    }
+
+    /// rate limiter used unless another one is set
+    fn default_rate_limiter(&self) -> RateLimiter {
+        RateLimiter::default()
+    }
+
+    /// endpoint and query searching posts with the tags
+    fn posts(tags: &str) -> (Self::Endpoint, Self::Query);
+
+    /// Search posts with the tags, `limit` is capped to `MAX_LIMIT`
+    fn search_posts(
+        client: &Client<Self>,
+        tags: &Self::SearchBuilder,
+        page: Page,
+        limit: i64,
+    ) -> impl Future<Output = Result<Vec<Self::Post>>> + Send {
+        let (endpoint, mut query) = Self::posts(tags.build().trim());
+        query.limit(limit.min(Self::MAX_LIMIT));
+        query.page(page);
+        let url = client.compose(endpoint, query);
+        async move {
+            let posts = client.fetch::<Self::Posts>(url?, Method::GET).await?;
+            Ok(posts.into_iter().collect())
+        }
+    }
+
+    /// Get the post, `None` if it does not exist or is hidden
+    ///
+    /// searches `id:<id>` unless the engine has an endpoint of a single post
+    fn get_post(
+        client: &Client<Self>,
+        id: i64,
+    ) -> impl Future<Output = Result<Option<Self::Post>>> + Send {
+        async move {
+            let mut tags = Self::SearchBuilder::new();
+            tags.add_tag(&format!("id:{}", id));
+            let posts = Self::search_posts(client, &tags, Page::Number(1), 1).await?;
+            Ok(posts.into_iter().next())
+        }
+    }
+}
+
+/// Board engine with wiki pages
+pub trait WikiBoardApi: BoardApi {
+    type WikiPage: BoardResponse;
+
+    /// endpoint and query of the wiki page with the title
+    fn wiki_page(title: &str) -> (Self::Endpoint, Self::Query);
+
+    /// Get the wiki page by the title
+    fn get_wiki(
+        client: &Client<Self>,
+        title: &str,
+    ) -> impl Future<Output = Result<Self::WikiPage>> + Send {
+        let (endpoint, query) = Self::wiki_page(title);
+        let url = client.compose(endpoint, query);
+        async move { client.fetch::<Self::WikiPage>(url?, Method::GET).await }
+    }
+}
+
+/// the post of the response, `None` if the board responded with 404
+pub(crate) fn found<T>(result: Result<T>) -> Result<Option<T>> {
+    match result {
+        Ok(post) => Ok(Some(post)),
+        Err(Error::NotFound(_)) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
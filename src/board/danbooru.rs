@@ -2,11 +2,25 @@ pub mod response;
 pub mod search;
 pub mod tags;
 
-use super::{BoardEndpoint, BoardQuery};
+use super::{found, safebooru, BoardApi, BoardEndpoint, BoardQuery, WikiBoardApi};
+use crate::client::Client;
+use reqwest::Method;
 use serde::{Deserialize, Serialize};
+use std::fmt;
 
 pub const HOST: &str = "https://danbooru.donmai.us";
 
+/// max number of posts per page
+pub const MAX_LIMIT: i64 = 200;
+
+/// fields of artists, including their urls which are not returned by default
+const ARTIST_FIELDS: &str =
+    "id,name,group_name,other_names,is_banned,is_deleted,created_at,updated_at,urls";
+/// fields of artist urls, including their artist
+const ARTIST_URL_FIELDS: &str = "id,artist_id,url,is_active,created_at,updated_at,artist";
+/// fields of uploads, including their media assets which are not returned by default
+const UPLOAD_FIELDS: &str = "id,source,referer_url,uploader_id,status,error,media_asset_count,created_at,updated_at,upload_media_assets[id,upload_id,media_asset_id,status,source_url,page_url,error,created_at,updated_at,media_asset]";
+
 // -- re-exports
 
 pub use search::SearchTagsBuilder;
@@ -67,6 +81,64 @@ impl ToString for FileExt {
     }
 }
 
+/// tag category
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(try_from = "u8", into = "u8")]
+pub enum TagCategory {
+    General,
+    Artist,
+    Copyright,
+    Character,
+    Meta,
+}
+
+impl TagCategory {
+    /// category id used by the api
+    pub fn id(&self) -> u8 {
+        match self {
+            TagCategory::General => 0,
+            TagCategory::Artist => 1,
+            TagCategory::Copyright => 3,
+            TagCategory::Character => 4,
+            TagCategory::Meta => 5,
+        }
+    }
+}
+
+impl TryFrom<u8> for TagCategory {
+    type Error = String;
+
+    fn try_from(id: u8) -> Result<Self, Self::Error> {
+        match id {
+            0 => Ok(TagCategory::General),
+            1 => Ok(TagCategory::Artist),
+            3 => Ok(TagCategory::Copyright),
+            4 => Ok(TagCategory::Character),
+            5 => Ok(TagCategory::Meta),
+            _ => Err(format!("unknown tag category: {}", id)),
+        }
+    }
+}
+
+impl From<TagCategory> for u8 {
+    fn from(category: TagCategory) -> Self {
+        category.id()
+    }
+}
+
+impl fmt::Display for TagCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            TagCategory::General => "general",
+            TagCategory::Artist => "artist",
+            TagCategory::Copyright => "copyright",
+            TagCategory::Character => "character",
+            TagCategory::Meta => "meta",
+        };
+        write!(f, "{}", s)
+    }
+}
+
 // -- danbooru types --
 
 /// danbooru api endpoint
@@ -75,6 +147,30 @@ pub enum Endpoint {
     Posts,
     Post(i64),
     WikiPages(String),
+    Profile,
+    Tags,
+    TagAliases,
+    TagImplications,
+    RelatedTag,
+    Autocomplete,
+    Artists,
+    Artist(i64),
+    ArtistUrls,
+    Pools,
+    Pool(i64),
+    FavoriteGroups,
+    FavoriteGroup(i64),
+    Comments,
+    Notes,
+    ArtistCommentaries,
+    PostVersions,
+    WikiPageVersions,
+    Counts,
+    PostVotes(i64),
+    Favorites,
+    Favorite(i64),
+    Uploads,
+    Upload(i64),
 }
 
 impl BoardEndpoint for Endpoint {
@@ -89,6 +185,87 @@ impl BoardEndpoint for Endpoint {
                 }
                 format!("/wiki_pages/{}.json", self.urlencode(title))
             }
+            Endpoint::Profile => "/profile.json".to_string(),
+            Endpoint::Tags => "/tags.json".to_string(),
+            Endpoint::TagAliases => "/tag_aliases.json".to_string(),
+            Endpoint::TagImplications => "/tag_implications.json".to_string(),
+            Endpoint::RelatedTag => "/related_tag.json".to_string(),
+            Endpoint::Autocomplete => "/autocomplete.json".to_string(),
+            Endpoint::Artists => "/artists.json".to_string(),
+            Endpoint::Artist(id) => format!("/artists/{}.json", id),
+            Endpoint::ArtistUrls => "/artist_urls.json".to_string(),
+            Endpoint::Pools => "/pools.json".to_string(),
+            Endpoint::Pool(id) => format!("/pools/{}.json", id),
+            Endpoint::FavoriteGroups => "/favorite_groups.json".to_string(),
+            Endpoint::FavoriteGroup(id) => format!("/favorite_groups/{}.json", id),
+            Endpoint::Comments => "/comments.json".to_string(),
+            Endpoint::Notes => "/notes.json".to_string(),
+            Endpoint::ArtistCommentaries => "/artist_commentaries.json".to_string(),
+            Endpoint::PostVersions => "/post_versions.json".to_string(),
+            Endpoint::WikiPageVersions => "/wiki_page_versions.json".to_string(),
+            Endpoint::Counts => "/counts/posts.json".to_string(),
+            Endpoint::PostVotes(post_id) => format!("/posts/{}/votes.json", post_id),
+            Endpoint::Favorites => "/favorites.json".to_string(),
+            Endpoint::Favorite(post_id) => format!("/favorites/{}.json", post_id),
+            Endpoint::Uploads => "/uploads.json".to_string(),
+            Endpoint::Upload(id) => format!("/uploads/{}.json", id),
+        }
+    }
+}
+
+/// pool category
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PoolCategory {
+    /// ordered posts such as comics
+    Series,
+    /// posts sharing a theme
+    Collection,
+}
+
+impl fmt::Display for PoolCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            PoolCategory::Series => "series",
+            PoolCategory::Collection => "collection",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+/// sort order of /tags.json
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TagOrder {
+    Name,
+    Date,
+    Count,
+    Similarity,
+}
+
+impl fmt::Display for TagOrder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            TagOrder::Name => "name",
+            TagOrder::Date => "date",
+            TagOrder::Count => "count",
+            TagOrder::Similarity => "similarity",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+/// vote on a post
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Vote {
+    Up,
+    Down,
+}
+
+impl Vote {
+    pub fn score(&self) -> i64 {
+        match self {
+            Vote::Up => 1,
+            Vote::Down => -1,
         }
     }
 }
@@ -109,19 +286,221 @@ impl Query {
         query
     }
 
+    /// request parameters for /counts/posts.json, which takes the same tags as /posts.json
+    pub fn counts(tags: &str) -> Self {
+        Query::posts(tags)
+    }
+
     /// request parameters for /post/{id}.json
     pub fn post() -> Self {
         Query::new()
     }
+
+    /// request parameters for /tags.json, `*` matches any characters
+    pub fn tags(name_matches: &str) -> Self {
+        let mut query = Query::new();
+        query.insert("search[name_matches]", name_matches);
+        query
+    }
+
+    /// request parameters for /tags.json with the exact names
+    pub fn tags_by_names(names: &[&str]) -> Self {
+        let mut query = Query::new();
+        query.insert("search[name_comma]", names.join(","));
+        query
+    }
+
+    /// filter tags by category
+    pub fn tag_category(&mut self, category: TagCategory) {
+        self.insert("search[category]", category.id());
+    }
+
+    /// sort tags
+    pub fn tag_order(&mut self, order: TagOrder) {
+        self.insert("search[order]", order.to_string());
+    }
+
+    /// request parameters for /tag_aliases.json and /tag_implications.json,
+    /// active relationships whose antecedent is one of the tags
+    pub fn tag_relationships(antecedents: &[&str]) -> Self {
+        let mut query = Query::new();
+        query.insert("search[antecedent_name_comma]", antecedents.join(","));
+        query.insert("search[status]", "active");
+        query
+    }
+
+    /// request parameters for /related_tag.json
+    pub fn related_tag(query: &str) -> Self {
+        let mut q = Query::new();
+        q.insert("query", query);
+        q
+    }
+
+    /// only return related tags of the category
+    pub fn related_category(&mut self, category: TagCategory) {
+        self.insert("category", category);
+    }
+
+    /// request parameters for /autocomplete.json
+    pub fn autocomplete(query: &str) -> Self {
+        let mut q = Query::new();
+        q.insert("search[query]", query);
+        q.insert("search[type]", "tag_query");
+        q
+    }
+
+    /// request parameters for /artists/{id}.json
+    pub fn artist() -> Self {
+        let mut query = Query::new();
+        query.insert("only", ARTIST_FIELDS);
+        query
+    }
+
+    /// request parameters for /artists.json, matching the name, group name and other names
+    pub fn artists(name_matches: &str) -> Self {
+        let mut query = Query::artist();
+        query.insert("search[any_name_matches]", name_matches);
+        query
+    }
+
+    /// request parameters for /artist_urls.json, `*` matches any characters
+    pub fn artist_urls(url_matches: &str) -> Self {
+        let mut query = Query::new();
+        query.insert("search[url_matches]", url_matches);
+        query.insert("only", ARTIST_URL_FIELDS);
+        query
+    }
+
+    /// request parameters for /pools.json, `*` matches any characters
+    pub fn pools(name_matches: &str) -> Self {
+        let mut query = Query::new();
+        query.insert("search[name_matches]", name_matches);
+        query
+    }
+
+    /// filter pools by category
+    pub fn pool_category(&mut self, category: PoolCategory) {
+        self.insert("search[category]", category);
+    }
+
+    /// request parameters for /favorite_groups.json, `*` matches any characters
+    pub fn favorite_groups(name_matches: &str) -> Self {
+        let mut query = Query::new();
+        query.insert("search[name_matches]", name_matches);
+        query
+    }
+
+    /// request parameters for /comments.json, visible comments of the post
+    pub fn comments(post_id: i64) -> Self {
+        let mut query = Query::new();
+        query.insert("group_by", "comment");
+        query.insert("search[post_id]", post_id);
+        query.insert("search[is_deleted]", false);
+        query
+    }
+
+    /// request parameters for /notes.json, active notes of the post
+    pub fn notes(post_id: i64) -> Self {
+        let mut query = Query::new();
+        query.insert("search[post_id]", post_id);
+        query.insert("search[is_active]", true);
+        query
+    }
+
+    /// request parameters for /artist_commentaries.json
+    pub fn artist_commentaries(post_id: i64) -> Self {
+        let mut query = Query::new();
+        query.insert("search[post_id]", post_id);
+        query
+    }
+
+    /// request parameters for /post_versions.json, edits of the post
+    pub fn post_versions(post_id: i64) -> Self {
+        let mut query = Query::new();
+        query.insert("search[post_id]", post_id);
+        query
+    }
+
+    /// request parameters for /wiki_page_versions.json, edits of the wiki page
+    pub fn wiki_page_versions(wiki_page_id: i64) -> Self {
+        let mut query = Query::new();
+        query.insert("search[wiki_page_id]", wiki_page_id);
+        query
+    }
+
+    /// request parameters for PUT /posts/{id}.json, set changes with the setters
+    pub fn post_update() -> Self {
+        Query::new()
+    }
+
+    /// replace tags of the post
+    pub fn tag_string(&mut self, tags: &str) {
+        self.insert("post[tag_string]", tags);
+    }
+
+    /// tags the edit is based on. The board applies only the difference from them to the current tags,
+    /// so tags edited by others in the meantime are not reverted.
+    pub fn old_tag_string(&mut self, tags: &str) {
+        self.insert("post[old_tag_string]", tags);
+    }
+
+    /// set rating of the post
+    pub fn post_rating(&mut self, rating: Rating) {
+        self.insert("post[rating]", rating);
+    }
+
+    /// set source of the post
+    pub fn post_source(&mut self, source: &str) {
+        self.insert("post[source]", source);
+    }
+
+    /// request parameters for POST /posts.json, set the post with the `post_update` setters
+    pub fn post_create(upload_media_asset_id: i64) -> Self {
+        let mut query = Query::new();
+        query.insert("upload_media_asset_id", upload_media_asset_id);
+        query
+    }
+
+    /// request parameters for /uploads/{id}.json
+    pub fn upload() -> Self {
+        let mut query = Query::new();
+        query.insert("only", UPLOAD_FIELDS);
+        query
+    }
+
+    /// request parameters for POST /uploads.json from the source url
+    pub fn upload_source(source: &str, referer: Option<&str>) -> Self {
+        let mut query = Query::new();
+        query.insert("upload[source]", source);
+        if let Some(referer) = referer {
+            query.insert("upload[referer_url]", referer);
+        }
+        query
+    }
+
+    /// request parameters for POST /posts/{id}/votes.json
+    pub fn vote(vote: Vote) -> Self {
+        let mut query = Query::new();
+        query.insert("score", vote.score());
+        query
+    }
+
+    /// request parameters for POST /favorites.json
+    pub fn favorite(post_id: i64) -> Self {
+        let mut query = Query::new();
+        query.insert("post_id", post_id);
+        query
+    }
+
+    /// exclude tags without posts
+    pub fn hide_empty(&mut self, hide_empty: bool) {
+        self.insert("search[hide_empty]", hide_empty);
+    }
 }
 
 impl BoardQuery for Query {
-    fn to_string(&self) -> String {
-        self.0
-            .iter()
-            .map(|(k, v)| format!("{}={}", k, v))
-            .collect::<Vec<String>>()
-            .join("&")
+    fn pairs(&self) -> Vec<(String, String)> {
+        self.0.clone()
     }
 
     fn insert<T: ToString, K: ToString>(&mut self, key: T, value: K) {
@@ -129,9 +508,64 @@ impl BoardQuery for Query {
     }
 }
 
+/// Danbooru engine and the boards running it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Danbooru {
+    #[default]
+    Danbooru,
+    Safebooru,
+}
+
+impl BoardApi for Danbooru {
+    type Endpoint = Endpoint;
+    type Query = Query;
+    type SearchBuilder = SearchTagsBuilder;
+    type Post = response::Post;
+    type Posts = response::Posts;
+
+    const MAX_LIMIT: i64 = MAX_LIMIT;
+    const AUTH_PARAMS: (&'static str, &'static str) = ("login", "api_key");
+    const BASIC_AUTH: bool = true;
+
+    fn host(&self) -> &str {
+        match self {
+            Danbooru::Danbooru => HOST,
+            Danbooru::Safebooru => safebooru::HOST,
+        }
+    }
+
+    fn name(&self) -> &str {
+        match self {
+            Danbooru::Danbooru => "danbooru",
+            Danbooru::Safebooru => "safebooru",
+        }
+    }
+
+    fn posts(tags: &str) -> (Endpoint, Query) {
+        (Endpoint::Posts, Query::posts(tags))
+    }
+
+    async fn get_post(
+        client: &Client<Self>,
+        id: i64,
+    ) -> crate::error::Result<Option<response::Post>> {
+        let url = client.compose(Endpoint::Post(id), Query::post())?;
+        found(client.fetch(url, Method::GET).await)
+    }
+}
+
+impl WikiBoardApi for Danbooru {
+    type WikiPage = response::WikiPage;
+
+    fn wiki_page(title: &str) -> (Endpoint, Query) {
+        (Endpoint::WikiPages(title.to_string()), Query::new())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::board::Page;
 
     #[test]
     fn test_rating_to_string() {
@@ -145,6 +579,39 @@ mod tests {
     fn test_endpoint_path() {
         assert_eq!(Endpoint::Posts.path(), "/posts.json");
         assert_eq!(Endpoint::Post(1234).path(), "/posts/1234.json");
+        assert_eq!(Endpoint::Profile.path(), "/profile.json");
+        assert_eq!(Endpoint::Tags.path(), "/tags.json");
+        assert_eq!(Endpoint::TagAliases.path(), "/tag_aliases.json");
+        assert_eq!(Endpoint::TagImplications.path(), "/tag_implications.json");
+        assert_eq!(Endpoint::RelatedTag.path(), "/related_tag.json");
+        assert_eq!(Endpoint::Autocomplete.path(), "/autocomplete.json");
+        assert_eq!(Endpoint::Artists.path(), "/artists.json");
+        assert_eq!(Endpoint::Artist(1234).path(), "/artists/1234.json");
+        assert_eq!(Endpoint::ArtistUrls.path(), "/artist_urls.json");
+        assert_eq!(Endpoint::Pools.path(), "/pools.json");
+        assert_eq!(Endpoint::Comments.path(), "/comments.json");
+        assert_eq!(Endpoint::PostVersions.path(), "/post_versions.json");
+        assert_eq!(
+            Endpoint::WikiPageVersions.path(),
+            "/wiki_page_versions.json"
+        );
+        assert_eq!(Endpoint::Counts.path(), "/counts/posts.json");
+        assert_eq!(Endpoint::PostVotes(1234).path(), "/posts/1234/votes.json");
+        assert_eq!(Endpoint::Favorites.path(), "/favorites.json");
+        assert_eq!(Endpoint::Favorite(1234).path(), "/favorites/1234.json");
+        assert_eq!(Endpoint::Uploads.path(), "/uploads.json");
+        assert_eq!(Endpoint::Upload(1234).path(), "/uploads/1234.json");
+        assert_eq!(Endpoint::Notes.path(), "/notes.json");
+        assert_eq!(
+            Endpoint::ArtistCommentaries.path(),
+            "/artist_commentaries.json"
+        );
+        assert_eq!(Endpoint::Pool(1234).path(), "/pools/1234.json");
+        assert_eq!(Endpoint::FavoriteGroups.path(), "/favorite_groups.json");
+        assert_eq!(
+            Endpoint::FavoriteGroup(1234).path(),
+            "/favorite_groups/1234.json"
+        );
     }
 
     #[test]
@@ -162,6 +629,130 @@ mod tests {
         assert_eq!(query.to_string(), "tags=1girl&limit=3&page=2");
     }
 
+    #[test]
+    fn test_query_tags() {
+        let mut query = Query::tags("cat_*");
+        query.tag_category(TagCategory::General);
+        query.tag_order(TagOrder::Count);
+        query.hide_empty(true);
+        query.limit(20);
+        assert_eq!(
+            query.to_string(),
+            "search%5Bname_matches%5D=cat_*&search%5Bcategory%5D=0&search%5Border%5D=count&search%5Bhide_empty%5D=true&limit=20"
+        );
+    }
+
+    #[test]
+    fn test_query_related_tag() {
+        let mut query = Query::related_tag("cat_ears");
+        query.related_category(TagCategory::Character);
+        assert_eq!(query.to_string(), "query=cat_ears&category=character");
+
+        let mut query = Query::autocomplete("cat_e");
+        query.limit(10);
+        assert_eq!(
+            query.to_string(),
+            "search%5Bquery%5D=cat_e&search%5Btype%5D=tag_query&limit=10"
+        );
+    }
+
+    #[test]
+    fn test_query_post_update() {
+        let mut query = Query::post_update();
+        query.tag_string("1girl cat_ears");
+        query.old_tag_string("1girl dog_ears");
+        query.post_rating(Rating::General);
+        assert_eq!(
+            query.to_string(),
+            "post%5Btag_string%5D=1girl+cat_ears&post%5Bold_tag_string%5D=1girl+dog_ears&post%5Brating%5D=g"
+        );
+
+        assert_eq!(Query::vote(Vote::Down).to_string(), "score=-1");
+    }
+
+    #[test]
+    fn test_tag_category() {
+        for category in [
+            TagCategory::General,
+            TagCategory::Artist,
+            TagCategory::Copyright,
+            TagCategory::Character,
+            TagCategory::Meta,
+        ] {
+            assert_eq!(TagCategory::try_from(category.id()), Ok(category));
+        }
+        assert!(TagCategory::try_from(2).is_err());
+        assert_eq!(serde_json::to_string(&TagCategory::Character).unwrap(), "4");
+        assert_eq!(
+            serde_json::from_str::<TagCategory>("1").unwrap(),
+            TagCategory::Artist
+        );
+    }
+
+    #[test]
+    fn test_query_cursor_page() {
+        let mut query = Query::posts("1girl");
+        query.page(Page::Before(1234));
+        assert_eq!(query.to_string(), "tags=1girl&page=b1234");
+
+        let mut query = Query::posts("1girl");
+        query.page(Page::After(1234));
+        assert_eq!(query.to_string(), "tags=1girl&page=a1234");
+    }
+
+    #[test]
+    fn test_query_urlencode() {
+        let mut query = Query::posts("tom_&_jerry rating:g,s");
+        query.limit(3);
+        assert_eq!(
+            query.to_string(),
+            "tags=tom_%26_jerry+rating%3Ag%2Cs&limit=3"
+        );
+    }
+
+    #[test]
+    fn test_query_round_trip() {
+        let tags = [
+            "k-on!",
+            "c.c.",
+            "tom_&_jerry",
+            "初音ミク",
+            "c++ 100% #1 a=b",
+            "score:>=10 order:rank",
+        ];
+
+        for tags in tags {
+            let mut query = Query::posts(tags);
+            query.page(2);
+
+            let parsed = url::form_urlencoded::parse(query.to_string().as_bytes())
+                .into_owned()
+                .collect::<Vec<_>>();
+            assert_eq!(
+                parsed,
+                vec![
+                    ("tags".to_string(), tags.to_string()),
+                    ("page".to_string(), "2".to_string())
+                ]
+            );
+        }
+    }
+
+    #[test]
+    fn test_wiki_title_urlencode() {
+        let endpoint = Endpoint::WikiPages("tom_&_jerry".to_string());
+        assert_eq!(endpoint.path(), "/wiki_pages/tom_%26_jerry.json");
+
+        let endpoint = Endpoint::WikiPages("初音ミク".to_string());
+        assert_eq!(
+            endpoint.path(),
+            "/wiki_pages/%E5%88%9D%E9%9F%B3%E3%83%9F%E3%82%AF.json"
+        );
+
+        let endpoint = Endpoint::WikiPages("a+b c".to_string());
+        assert_eq!(endpoint.path(), "/wiki_pages/a%2Bb%20c.json");
+    }
+
     #[test]
     fn test_fetch_number_title_wiki() {
         let title = "2024";
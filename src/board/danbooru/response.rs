@@ -1,8 +1,48 @@
+pub mod artist;
+pub mod artist_commentary;
+pub mod artist_url;
+pub mod autocomplete;
+pub mod comment;
+pub mod counts;
+pub mod favorite;
+pub mod favorite_group;
+pub mod note;
+pub mod pool;
 pub mod post;
+pub mod post_version;
+pub mod post_vote;
 pub mod posts;
+pub mod profile;
+pub mod related_tag;
+pub mod tag;
+pub mod tag_relationship;
+pub mod tags;
+pub mod upload;
 pub mod wiki_page;
+pub mod wiki_page_version;
 
 // -- re-exports
+pub use artist::{Artist, Artists};
+pub use artist_commentary::{ArtistCommentaries, ArtistCommentary};
+pub use artist_url::{ArtistUrl, ArtistUrls};
+pub use autocomplete::{Autocomplete, AutocompleteItem, AutocompleteKind};
+pub use comment::{Comment, Comments};
+pub use counts::Counts;
+pub use favorite::Favorite;
+pub use favorite_group::{FavoriteGroup, FavoriteGroups};
+pub use note::{Note, Notes};
+pub use pool::{Pool, Pools};
 pub use post::Post;
+pub use post_version::{PostVersion, PostVersions};
+pub use post_vote::PostVote;
 pub use posts::Posts;
+pub use profile::Profile;
+pub use related_tag::{RelatedTag, RelatedTags};
+pub use tag::Tag;
+pub use tag_relationship::{
+    TagAlias, TagAliases, TagImplication, TagImplications, TagRelationship, TagRelationshipStatus,
+};
+pub use tags::Tags;
+pub use upload::{Upload, UploadMediaAsset, UploadMediaAssetStatus, UploadStatus};
 pub use wiki_page::WikiPage;
+pub use wiki_page_version::{WikiPageVersion, WikiPageVersions};
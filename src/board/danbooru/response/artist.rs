@@ -0,0 +1,74 @@
+use super::artist_url::ArtistUrl;
+use crate::board::BoardResponse;
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+
+/// response type for /artists/{id}
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Artist {
+    pub id: i64,
+    /// the artist tag
+    pub name: String,
+    pub group_name: String,
+    pub other_names: Vec<String>,
+    pub is_banned: bool,
+    pub is_deleted: bool,
+    pub created_at: String,
+    pub updated_at: String,
+    /// only returned when requested with `only=...,urls`
+    #[serde(default)]
+    pub urls: Vec<ArtistUrl>,
+}
+
+impl Artist {
+    /// urls which the artist still uses
+    pub fn active_urls(&self) -> impl Iterator<Item = &str> {
+        self.urls
+            .iter()
+            .filter(|u| u.is_active)
+            .map(|u| u.url.as_str())
+    }
+}
+
+/// response type for /artists
+pub type Artists = Vec<Artist>;
+
+impl BoardResponse for Artist {
+    fn from_str(s: &str) -> Result<Self> {
+        let artist: Artist = serde_json::from_str(s).map_err(|e| Error::decode(e, s))?;
+        Ok(artist)
+    }
+}
+
+impl BoardResponse for Artists {
+    fn from_str(s: &str) -> Result<Self> {
+        let artists: Artists = serde_json::from_str(s).map_err(|e| Error::decode(e, s))?;
+        Ok(artists)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_artists() {
+        let artists = Artists::from_str(include_str!(
+            "../../../../tests/fixtures/danbooru/artists.json"
+        ))
+        .unwrap();
+
+        assert_eq!(artists.len(), 2);
+        assert_eq!(artists[0].name, "example_(artist)");
+        assert_eq!(artists[0].other_names, vec!["例のアーティスト", "example"]);
+        assert_eq!(
+            artists[0].active_urls().collect::<Vec<_>>(),
+            vec![
+                "https://twitter.com/example",
+                "https://www.pixiv.net/users/1234567"
+            ]
+        );
+        assert!(artists[1].is_banned);
+        assert!(artists[1].urls.is_empty());
+    }
+}
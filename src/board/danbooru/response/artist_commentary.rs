@@ -0,0 +1,35 @@
+use crate::board::BoardResponse;
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+
+/// response type for /artist_commentaries/{id}
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArtistCommentary {
+    pub id: i64,
+    pub post_id: i64,
+    /// written by the artist
+    pub original_title: String,
+    pub original_description: String,
+    /// translated by users
+    pub translated_title: String,
+    pub translated_description: String,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl ArtistCommentary {
+    pub fn has_translation(&self) -> bool {
+        !self.translated_title.is_empty() || !self.translated_description.is_empty()
+    }
+}
+
+/// response type for /artist_commentaries
+pub type ArtistCommentaries = Vec<ArtistCommentary>;
+
+impl BoardResponse for ArtistCommentaries {
+    fn from_str(s: &str) -> Result<Self> {
+        let commentaries: ArtistCommentaries =
+            serde_json::from_str(s).map_err(|e| Error::decode(e, s))?;
+        Ok(commentaries)
+    }
+}
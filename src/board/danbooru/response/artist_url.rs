@@ -0,0 +1,28 @@
+use super::artist::Artist;
+use crate::board::BoardResponse;
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+
+/// response type for /artist_urls/{id}
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArtistUrl {
+    pub id: i64,
+    pub artist_id: i64,
+    pub url: String,
+    /// whether the artist still uses the url
+    pub is_active: bool,
+    pub created_at: String,
+    pub updated_at: String,
+    /// only returned when requested with `only=...,artist`
+    pub artist: Option<Box<Artist>>,
+}
+
+/// response type for /artist_urls
+pub type ArtistUrls = Vec<ArtistUrl>;
+
+impl BoardResponse for ArtistUrls {
+    fn from_str(s: &str) -> Result<Self> {
+        let urls: ArtistUrls = serde_json::from_str(s).map_err(|e| Error::decode(e, s))?;
+        Ok(urls)
+    }
+}
@@ -0,0 +1,63 @@
+use crate::board::danbooru::TagCategory;
+use crate::board::BoardResponse;
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+
+/// why the item matched the query
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum AutocompleteKind {
+    Tag,
+    TagWord,
+    TagAlias,
+    TagAutocorrect,
+    TagOtherName,
+    TagAbbreviation,
+    #[serde(other)]
+    Other,
+}
+
+/// autocomplete candidate
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AutocompleteItem {
+    #[serde(rename = "type")]
+    pub kind: AutocompleteKind,
+    pub label: String,
+    /// the tag to insert
+    pub value: String,
+    pub category: Option<TagCategory>,
+    pub post_count: Option<i64>,
+    /// the name the query matched, if it is not the tag itself
+    pub antecedent: Option<String>,
+}
+
+/// response type for /autocomplete
+pub type Autocomplete = Vec<AutocompleteItem>;
+
+impl BoardResponse for Autocomplete {
+    fn from_str(s: &str) -> Result<Self> {
+        let items: Autocomplete = serde_json::from_str(s).map_err(|e| Error::decode(e, s))?;
+        Ok(items)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_autocomplete() {
+        let items = Autocomplete::from_str(include_str!(
+            "../../../../tests/fixtures/danbooru/autocomplete.json"
+        ))
+        .unwrap();
+
+        assert_eq!(items.len(), 3);
+        assert_eq!(items[0].kind, AutocompleteKind::TagWord);
+        assert_eq!(items[0].value, "cat_ears");
+        assert_eq!(items[0].category, Some(TagCategory::General));
+        assert_eq!(items[1].kind, AutocompleteKind::TagAlias);
+        assert_eq!(items[1].antecedent.as_deref(), Some("cat_ear"));
+        assert_eq!(items[2].kind, AutocompleteKind::Other);
+    }
+}
@@ -0,0 +1,30 @@
+use crate::board::BoardResponse;
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+
+/// response type for /comments/{id}
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Comment {
+    pub id: i64,
+    pub post_id: i64,
+    pub creator_id: i64,
+    pub updater_id: Option<i64>,
+    /// DText
+    pub body: String,
+    pub score: i64,
+    pub do_not_bump_post: bool,
+    pub is_deleted: bool,
+    pub is_sticky: bool,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// response type for /comments
+pub type Comments = Vec<Comment>;
+
+impl BoardResponse for Comments {
+    fn from_str(s: &str) -> Result<Self> {
+        let comments: Comments = serde_json::from_str(s).map_err(|e| Error::decode(e, s))?;
+        Ok(comments)
+    }
+}
@@ -0,0 +1,46 @@
+use crate::board::BoardResponse;
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+
+/// response type for /counts/posts
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Counts {
+    pub counts: PostCounts,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PostCounts {
+    /// null when counting timed out (e.g. too many matching posts)
+    pub posts: Option<i64>,
+}
+
+impl Counts {
+    /// number of posts matching the search
+    pub fn posts(&self) -> Option<i64> {
+        self.counts.posts
+    }
+}
+
+impl BoardResponse for Counts {
+    fn from_str(s: &str) -> Result<Self> {
+        let counts: Counts = serde_json::from_str(s).map_err(|e| Error::decode(e, s))?;
+        Ok(counts)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_counts() {
+        let counts = Counts::from_str(include_str!(
+            "../../../../tests/fixtures/danbooru/counts.json"
+        ))
+        .unwrap();
+        assert_eq!(counts.posts(), Some(1234));
+
+        let counts = Counts::from_str(r#"{"counts":{"posts":null}}"#).unwrap();
+        assert_eq!(counts.posts(), None);
+    }
+}
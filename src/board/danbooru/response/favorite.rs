@@ -0,0 +1,33 @@
+use crate::board::BoardResponse;
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+
+/// response type for /favorites/{id}
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Favorite {
+    pub id: i64,
+    pub user_id: i64,
+    pub post_id: i64,
+}
+
+impl BoardResponse for Favorite {
+    fn from_str(s: &str) -> Result<Self> {
+        let favorite: Favorite = serde_json::from_str(s).map_err(|e| Error::decode(e, s))?;
+        Ok(favorite)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_favorite() {
+        let favorite = Favorite::from_str(include_str!(
+            "../../../../tests/fixtures/danbooru/favorite.json"
+        ))
+        .unwrap();
+
+        assert_eq!(favorite.post_id, 8000000);
+    }
+}
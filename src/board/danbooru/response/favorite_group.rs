@@ -0,0 +1,51 @@
+use crate::board::BoardResponse;
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+
+/// response type for /favorite_groups/{id}
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FavoriteGroup {
+    pub id: i64,
+    pub name: String,
+    pub creator_id: i64,
+    /// posts in the order the creator arranged
+    pub post_ids: Vec<i64>,
+    pub is_public: bool,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// response type for /favorite_groups
+pub type FavoriteGroups = Vec<FavoriteGroup>;
+
+impl BoardResponse for FavoriteGroup {
+    fn from_str(s: &str) -> Result<Self> {
+        let group: FavoriteGroup = serde_json::from_str(s).map_err(|e| Error::decode(e, s))?;
+        Ok(group)
+    }
+}
+
+impl BoardResponse for FavoriteGroups {
+    fn from_str(s: &str) -> Result<Self> {
+        let groups: FavoriteGroups = serde_json::from_str(s).map_err(|e| Error::decode(e, s))?;
+        Ok(groups)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_favorite_groups() {
+        let groups = FavoriteGroups::from_str(include_str!(
+            "../../../../tests/fixtures/danbooru/favorite_groups.json"
+        ))
+        .unwrap();
+
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].name, "cats");
+        assert_eq!(groups[0].post_ids, vec![8100003, 8000000]);
+        assert!(groups[0].is_public);
+    }
+}
@@ -0,0 +1,49 @@
+use crate::board::BoardResponse;
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+
+/// response type for /notes/{id}
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Note {
+    pub id: i64,
+    pub post_id: i64,
+    /// box in pixels of the original image
+    pub x: i64,
+    pub y: i64,
+    pub width: i64,
+    pub height: i64,
+    /// HTML
+    pub body: String,
+    pub is_active: bool,
+    pub version: i64,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// response type for /notes
+pub type Notes = Vec<Note>;
+
+impl BoardResponse for Notes {
+    fn from_str(s: &str) -> Result<Self> {
+        let notes: Notes = serde_json::from_str(s).map_err(|e| Error::decode(e, s))?;
+        Ok(notes)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_notes() {
+        let notes = Notes::from_str(include_str!(
+            "../../../../tests/fixtures/danbooru/notes.json"
+        ))
+        .unwrap();
+
+        assert_eq!(notes.len(), 2);
+        assert_eq!((notes[0].x, notes[0].y), (120, 340));
+        assert_eq!((notes[0].width, notes[0].height), (200, 80));
+        assert_eq!(notes[1].body, "<tn>Meow?</tn>");
+    }
+}
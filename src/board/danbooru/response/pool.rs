@@ -0,0 +1,62 @@
+use crate::board::danbooru::PoolCategory;
+use crate::board::BoardResponse;
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+
+/// response type for /pools/{id}
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Pool {
+    pub id: i64,
+    pub name: String,
+    pub category: PoolCategory,
+    /// posts in the pool order
+    pub post_ids: Vec<i64>,
+    pub post_count: i64,
+    pub description: String,
+    pub is_active: bool,
+    pub is_deleted: bool,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl Pool {
+    /// name with spaces instead of underscores
+    pub fn pretty_name(&self) -> String {
+        self.name.replace('_', " ")
+    }
+}
+
+/// response type for /pools
+pub type Pools = Vec<Pool>;
+
+impl BoardResponse for Pool {
+    fn from_str(s: &str) -> Result<Self> {
+        let pool: Pool = serde_json::from_str(s).map_err(|e| Error::decode(e, s))?;
+        Ok(pool)
+    }
+}
+
+impl BoardResponse for Pools {
+    fn from_str(s: &str) -> Result<Self> {
+        let pools: Pools = serde_json::from_str(s).map_err(|e| Error::decode(e, s))?;
+        Ok(pools)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_pool() {
+        let pool = Pool::from_str(include_str!(
+            "../../../../tests/fixtures/danbooru/pool.json"
+        ))
+        .unwrap();
+
+        assert_eq!(pool.id, 12345);
+        assert_eq!(pool.category, PoolCategory::Series);
+        assert_eq!(pool.post_ids, vec![8100002, 8100001, 8100003]);
+        assert_eq!(pool.pretty_name(), "Example Comic (Example Artist)");
+    }
+}
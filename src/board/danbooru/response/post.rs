@@ -1,6 +1,6 @@
 use crate::board::danbooru::{FileExt, Rating};
 use crate::board::BoardResponse;
-use anyhow::Result;
+use crate::error::{Error, Result};
 use serde::{Deserialize, Serialize};
 
 /// response type for /post
@@ -72,7 +72,7 @@ pub struct Post {
     pub bit_flags: i64,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct MediaAsset {
     pub id: i64,
     pub created_at: String,
@@ -90,7 +90,7 @@ pub struct MediaAsset {
     pub variants: Option<Vec<Variant>>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Variant {
     #[serde(rename = "type")]
     pub variant_type: String,
@@ -102,7 +102,7 @@ pub struct Variant {
 
 impl BoardResponse for Post {
     fn from_str(s: &str) -> Result<Self> {
-        let post: Post = serde_json::from_str(s)?;
+        let post: Post = serde_json::from_str(s).map_err(|e| Error::decode(e, s))?;
         Ok(post)
     }
 }
@@ -0,0 +1,73 @@
+use crate::board::danbooru::Rating;
+use crate::board::BoardResponse;
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+
+/// response type for /post_versions/{id}
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PostVersion {
+    pub id: i64,
+    pub post_id: i64,
+    /// number of the edit, starting from 1
+    pub version: i64,
+    /// all tags after the edit
+    pub tags: String,
+    pub added_tags: Vec<String>,
+    pub removed_tags: Vec<String>,
+    /// added tags which were removed by a later edit
+    #[serde(default)]
+    pub obsolete_added_tags: String,
+    /// removed tags which were added back by a later edit
+    #[serde(default)]
+    pub obsolete_removed_tags: String,
+    pub rating: Rating,
+    pub rating_changed: bool,
+    pub parent_id: Option<i64>,
+    pub parent_changed: bool,
+    pub source: String,
+    pub source_changed: bool,
+    /// missing for edits done by the system
+    pub updater_id: Option<i64>,
+    pub updated_at: String,
+}
+
+impl PostVersion {
+    /// whether the edit changed tags
+    pub fn tags_changed(&self) -> bool {
+        !self.added_tags.is_empty() || !self.removed_tags.is_empty()
+    }
+}
+
+/// response type for /post_versions
+pub type PostVersions = Vec<PostVersion>;
+
+impl BoardResponse for PostVersions {
+    fn from_str(s: &str) -> Result<Self> {
+        let versions: PostVersions = serde_json::from_str(s).map_err(|e| Error::decode(e, s))?;
+        Ok(versions)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_post_versions() {
+        let versions = PostVersions::from_str(include_str!(
+            "../../../../tests/fixtures/danbooru/post_versions.json"
+        ))
+        .unwrap();
+
+        assert_eq!(versions.len(), 2);
+        assert_eq!(versions[0].version, 2);
+        assert_eq!(versions[0].added_tags, vec!["cat_ears"]);
+        assert_eq!(versions[0].removed_tags, vec!["dog_ears"]);
+        assert!(versions[0].tags_changed());
+        assert!(!versions[0].rating_changed);
+
+        assert_eq!(versions[1].rating, Rating::General);
+        assert!(versions[1].rating_changed);
+        assert_eq!(versions[1].updater_id, None);
+    }
+}
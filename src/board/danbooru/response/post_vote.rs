@@ -0,0 +1,40 @@
+use crate::board::BoardResponse;
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+
+/// response type for /post_votes/{id}
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PostVote {
+    pub id: i64,
+    pub post_id: i64,
+    pub user_id: i64,
+    /// 1 for upvotes, -1 for downvotes
+    pub score: i64,
+    #[serde(default)]
+    pub is_deleted: bool,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl BoardResponse for PostVote {
+    fn from_str(s: &str) -> Result<Self> {
+        let vote: PostVote = serde_json::from_str(s).map_err(|e| Error::decode(e, s))?;
+        Ok(vote)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_post_vote() {
+        let vote = PostVote::from_str(include_str!(
+            "../../../../tests/fixtures/danbooru/post_vote.json"
+        ))
+        .unwrap();
+
+        assert_eq!(vote.post_id, 8000000);
+        assert_eq!(vote.score, 1);
+    }
+}
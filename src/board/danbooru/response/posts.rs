@@ -1,13 +1,13 @@
 use super::post::Post;
 use crate::board::BoardResponse;
-use anyhow::Result;
+use crate::error::{Error, Result};
 
 /// response type for /posts    
 pub type Posts = Vec<Post>;
 
 impl BoardResponse for Posts {
     fn from_str(s: &str) -> Result<Self> {
-        let posts: Posts = serde_json::from_str(s)?;
+        let posts: Posts = serde_json::from_str(s).map_err(|e| Error::decode(e, s))?;
         Ok(posts)
     }
 }
@@ -0,0 +1,62 @@
+use crate::board::BoardResponse;
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+
+/// response type for /profile
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Profile {
+    /// missing for anonymous users
+    pub id: Option<i64>,
+    pub name: String,
+    pub level: i64,
+    pub level_string: String,
+    pub created_at: Option<String>,
+    #[serde(default)]
+    pub is_banned: bool,
+
+    // limits
+    pub tag_query_limit: Option<i64>,
+    pub favorite_group_limit: Option<i64>,
+    pub max_saved_searches: Option<i64>,
+    pub statement_timeout: Option<i64>,
+    pub per_page: Option<i64>,
+
+    // counts
+    pub post_upload_count: Option<i64>,
+    pub post_update_count: Option<i64>,
+    pub note_update_count: Option<i64>,
+    pub favorite_count: Option<i64>,
+}
+
+impl Profile {
+    /// whether the user is logged in
+    pub fn is_anonymous(&self) -> bool {
+        self.id.is_none()
+    }
+}
+
+impl BoardResponse for Profile {
+    fn from_str(s: &str) -> Result<Self> {
+        let profile: Profile = serde_json::from_str(s).map_err(|e| Error::decode(e, s))?;
+        Ok(profile)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_profile() {
+        let profile = Profile::from_str(include_str!(
+            "../../../../tests/fixtures/danbooru/profile.json"
+        ))
+        .unwrap();
+
+        assert_eq!(profile.id, Some(123456));
+        assert_eq!(profile.name, "example_user");
+        assert_eq!(profile.level_string, "Gold");
+        assert_eq!(profile.tag_query_limit, Some(6));
+        assert!(!profile.is_anonymous());
+    }
+}
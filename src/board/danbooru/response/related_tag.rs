@@ -0,0 +1,62 @@
+use super::tag::Tag;
+use crate::board::danbooru::TagCategory;
+use crate::board::BoardResponse;
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+
+/// tag frequently used with the query
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RelatedTag {
+    pub tag: Tag,
+    /// share of the query's posts which have the tag
+    pub frequency: f64,
+    pub cosine_similarity: f64,
+    pub jaccard_similarity: f64,
+    pub overlap_coefficient: f64,
+}
+
+/// response type for /related_tag
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RelatedTags {
+    pub query: String,
+    pub post_count: Option<i64>,
+    pub category: Option<String>,
+    pub tags: Vec<RelatedTag>,
+    /// tags linked from the wiki page of the query
+    #[serde(default)]
+    pub wiki_page_tags: Vec<Tag>,
+}
+
+impl RelatedTags {
+    /// related tags of the category
+    pub fn of_category(&self, category: TagCategory) -> impl Iterator<Item = &RelatedTag> {
+        self.tags.iter().filter(move |t| t.tag.category == category)
+    }
+}
+
+impl BoardResponse for RelatedTags {
+    fn from_str(s: &str) -> Result<Self> {
+        let related_tags: RelatedTags = serde_json::from_str(s).map_err(|e| Error::decode(e, s))?;
+        Ok(related_tags)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_related_tags() {
+        let related = RelatedTags::from_str(include_str!(
+            "../../../../tests/fixtures/danbooru/related_tag.json"
+        ))
+        .unwrap();
+
+        assert_eq!(related.query, "cat_ears");
+        assert_eq!(related.tags.len(), 3);
+        assert_eq!(related.tags[1].tag.name, "animal_ears");
+        assert!(related.tags[1].cosine_similarity > 0.5);
+        assert_eq!(related.of_category(TagCategory::Character).count(), 1);
+        assert_eq!(related.wiki_page_tags.len(), 1);
+    }
+}
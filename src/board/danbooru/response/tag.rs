@@ -0,0 +1,25 @@
+use crate::board::danbooru::TagCategory;
+use crate::board::BoardResponse;
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+
+/// response type for /tags/{id}
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Tag {
+    pub id: i64,
+    pub name: String,
+    pub post_count: i64,
+    pub category: TagCategory,
+    pub created_at: String,
+    pub updated_at: String,
+    pub is_deprecated: bool,
+    #[serde(default)]
+    pub words: Vec<String>,
+}
+
+impl BoardResponse for Tag {
+    fn from_str(s: &str) -> Result<Self> {
+        let tag: Tag = serde_json::from_str(s).map_err(|e| Error::decode(e, s))?;
+        Ok(tag)
+    }
+}
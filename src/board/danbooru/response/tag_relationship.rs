@@ -0,0 +1,86 @@
+use crate::board::BoardResponse;
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+
+/// status of tag aliases and implications
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TagRelationshipStatus {
+    Active,
+    Deleted,
+    Retired,
+    Pending,
+    Queued,
+    Processing,
+    /// failed to process, the status contains the error message (e.g. `error: ...`)
+    #[serde(other)]
+    Error,
+}
+
+/// response type for /tag_aliases/{id} and /tag_implications/{id}
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TagRelationship {
+    pub id: i64,
+    pub antecedent_name: String,
+    pub consequent_name: String,
+    pub status: TagRelationshipStatus,
+    pub creator_id: Option<i64>,
+    pub approver_id: Option<i64>,
+    pub forum_topic_id: Option<i64>,
+    pub forum_post_id: Option<i64>,
+    pub reason: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl TagRelationship {
+    pub fn is_active(&self) -> bool {
+        self.status == TagRelationshipStatus::Active
+    }
+}
+
+/// `antecedent_name` is replaced with `consequent_name`
+pub type TagAlias = TagRelationship;
+/// `antecedent_name` implies `consequent_name`
+pub type TagImplication = TagRelationship;
+
+/// response type for /tag_aliases
+pub type TagAliases = Vec<TagAlias>;
+/// response type for /tag_implications
+pub type TagImplications = Vec<TagImplication>;
+
+impl BoardResponse for TagRelationship {
+    fn from_str(s: &str) -> Result<Self> {
+        let relationship: TagRelationship =
+            serde_json::from_str(s).map_err(|e| Error::decode(e, s))?;
+        Ok(relationship)
+    }
+}
+
+impl BoardResponse for Vec<TagRelationship> {
+    fn from_str(s: &str) -> Result<Self> {
+        let relationships: Vec<TagRelationship> =
+            serde_json::from_str(s).map_err(|e| Error::decode(e, s))?;
+        Ok(relationships)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_tag_implications() {
+        let implications = TagImplications::from_str(include_str!(
+            "../../../../tests/fixtures/danbooru/tag_implications.json"
+        ))
+        .unwrap();
+
+        assert_eq!(implications.len(), 3);
+        assert_eq!(implications[0].antecedent_name, "cat_ears");
+        assert_eq!(implications[0].consequent_name, "animal_ears");
+        assert!(implications[0].is_active());
+        assert_eq!(implications[1].status, TagRelationshipStatus::Deleted);
+        assert_eq!(implications[2].status, TagRelationshipStatus::Error);
+    }
+}
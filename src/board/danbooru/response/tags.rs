@@ -0,0 +1,34 @@
+use super::tag::Tag;
+use crate::board::BoardResponse;
+use crate::error::{Error, Result};
+
+/// response type for /tags
+pub type Tags = Vec<Tag>;
+
+impl BoardResponse for Tags {
+    fn from_str(s: &str) -> Result<Self> {
+        let tags: Tags = serde_json::from_str(s).map_err(|e| Error::decode(e, s))?;
+        Ok(tags)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::board::danbooru::TagCategory;
+
+    #[test]
+    fn test_parse_tags() {
+        let tags = Tags::from_str(include_str!(
+            "../../../../tests/fixtures/danbooru/tags.json"
+        ))
+        .unwrap();
+
+        assert_eq!(tags.len(), 3);
+        assert_eq!(tags[0].name, "cat_ears");
+        assert_eq!(tags[0].category, TagCategory::General);
+        assert_eq!(tags[0].words, vec!["cat", "ears"]);
+        assert_eq!(tags[1].category, TagCategory::Character);
+        assert!(tags[2].is_deprecated);
+    }
+}
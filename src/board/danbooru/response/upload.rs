@@ -0,0 +1,111 @@
+use super::post::MediaAsset;
+use crate::board::BoardResponse;
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+
+/// status of uploads
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UploadStatus {
+    Pending,
+    Processing,
+    Completed,
+    /// failed to process, the status contains the error message (e.g. `error: ...`)
+    #[serde(other)]
+    Error,
+}
+
+/// status of media assets of uploads
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UploadMediaAssetStatus {
+    Pending,
+    Processing,
+    Active,
+    #[serde(other)]
+    Failed,
+}
+
+/// response type for /uploads/{id}
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Upload {
+    pub id: i64,
+    /// empty for file uploads
+    pub source: String,
+    pub referer_url: Option<String>,
+    pub uploader_id: i64,
+    pub status: UploadStatus,
+    pub error: Option<String>,
+    pub media_asset_count: i64,
+    pub created_at: String,
+    pub updated_at: String,
+    /// files of the upload, e.g. each page of a pixiv post
+    #[serde(default)]
+    pub upload_media_assets: Vec<UploadMediaAsset>,
+}
+
+impl Upload {
+    /// whether the upload will not change anymore
+    pub fn is_finished(&self) -> bool {
+        matches!(self.status, UploadStatus::Completed | UploadStatus::Error)
+    }
+
+    /// media assets which can be posted
+    pub fn active_media_assets(&self) -> impl Iterator<Item = &UploadMediaAsset> {
+        self.upload_media_assets
+            .iter()
+            .filter(|asset| asset.status == UploadMediaAssetStatus::Active)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UploadMediaAsset {
+    /// passed to POST /posts.json as `upload_media_asset_id`
+    pub id: i64,
+    pub upload_id: i64,
+    pub media_asset_id: i64,
+    pub status: UploadMediaAssetStatus,
+    pub source_url: String,
+    pub page_url: Option<String>,
+    pub error: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+    pub media_asset: Option<MediaAsset>,
+}
+
+impl BoardResponse for Upload {
+    fn from_str(s: &str) -> Result<Self> {
+        let upload: Upload = serde_json::from_str(s).map_err(|e| Error::decode(e, s))?;
+        Ok(upload)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_upload() {
+        let upload = Upload::from_str(include_str!(
+            "../../../../tests/fixtures/danbooru/upload.json"
+        ))
+        .unwrap();
+
+        assert_eq!(upload.status, UploadStatus::Completed);
+        assert!(upload.is_finished());
+        assert_eq!(upload.active_media_assets().count(), 1);
+        let asset = &upload.upload_media_assets[0];
+        assert_eq!(asset.media_asset.as_ref().unwrap().id, asset.media_asset_id);
+    }
+
+    #[test]
+    fn test_parse_upload_error() {
+        let upload = Upload::from_str(
+            r#"{"id":1,"source":"","referer_url":null,"uploader_id":1,"status":"error: Not an image","error":"Not an image","media_asset_count":0,"created_at":"2024-08-04T10:00:00.000-04:00","updated_at":"2024-08-04T10:00:01.000-04:00"}"#,
+        )
+        .unwrap();
+
+        assert_eq!(upload.status, UploadStatus::Error);
+        assert!(upload.upload_media_assets.is_empty());
+    }
+}
@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
 
 use crate::board::BoardResponse;
+use crate::error::{Error, Result};
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct WikiPage {
@@ -15,8 +16,8 @@ pub struct WikiPage {
 }
 
 impl BoardResponse for WikiPage {
-    fn from_str(s: &str) -> anyhow::Result<Self> {
-        let wiki_page: WikiPage = serde_json::from_str(s)?;
+    fn from_str(s: &str) -> Result<Self> {
+        let wiki_page: WikiPage = serde_json::from_str(s).map_err(|e| Error::decode(e, s))?;
         Ok(wiki_page)
     }
 }
@@ -0,0 +1,47 @@
+use crate::board::BoardResponse;
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+
+/// response type for /wiki_page_versions/{id}
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WikiPageVersion {
+    pub id: i64,
+    pub wiki_page_id: i64,
+    pub title: String,
+    pub body: String,
+    pub other_names: Vec<String>,
+    pub is_locked: bool,
+    pub is_deleted: bool,
+    pub updater_id: Option<i64>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// response type for /wiki_page_versions
+pub type WikiPageVersions = Vec<WikiPageVersion>;
+
+impl BoardResponse for WikiPageVersions {
+    fn from_str(s: &str) -> Result<Self> {
+        let versions: WikiPageVersions =
+            serde_json::from_str(s).map_err(|e| Error::decode(e, s))?;
+        Ok(versions)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_wiki_page_versions() {
+        let versions = WikiPageVersions::from_str(include_str!(
+            "../../../../tests/fixtures/danbooru/wiki_page_versions.json"
+        ))
+        .unwrap();
+
+        assert_eq!(versions.len(), 1);
+        assert_eq!(versions[0].wiki_page_id, 12345);
+        assert_eq!(versions[0].title, "cat_ears");
+        assert_eq!(versions[0].other_names, vec!["猫耳"]);
+    }
+}
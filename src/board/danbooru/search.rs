@@ -6,6 +6,118 @@ use crate::board::BoardSearchTagsBuilder;
 
 use super::{FileExt, Rating};
 
+/// names of metatags, which are not tags on the board
+pub const METATAGS: [&str; 101] = [
+    "active_child_count",
+    "active_children",
+    "active_comment_count",
+    "active_comments",
+    "active_note_count",
+    "active_notes",
+    "active_pool_count",
+    "active_pools",
+    "age",
+    "ai",
+    "appeal_count",
+    "appealer",
+    "appeals",
+    "approval_count",
+    "approvals",
+    "approver",
+    "artcomm",
+    "arttags",
+    "chartags",
+    "child",
+    "child_count",
+    "children",
+    "collection_pool_count",
+    "collection_pools",
+    "comm",
+    "comment",
+    "comment_count",
+    "commentary",
+    "commentaryupdater",
+    "commenter",
+    "comments",
+    "copytags",
+    "date",
+    "deleted_child_count",
+    "deleted_children",
+    "deleted_comment_count",
+    "deleted_comments",
+    "deleted_note_count",
+    "deleted_notes",
+    "deleted_pool_count",
+    "deleted_pools",
+    "disapproved",
+    "downvote",
+    "downvoter",
+    "downvotes",
+    "duration",
+    "embedded",
+    "exif",
+    "fav",
+    "favcount",
+    "favgroup",
+    "filesize",
+    "filetype",
+    "flag_count",
+    "flagger",
+    "flags",
+    "gentags",
+    "has",
+    "height",
+    "id",
+    "is",
+    "limit",
+    "md5",
+    "metatags",
+    "mpixels",
+    "note",
+    "note_count",
+    "noter",
+    "notes",
+    "noteupdater",
+    "order",
+    "ordfav",
+    "ordfavgroup",
+    "ordpool",
+    "parent",
+    "pixiv",
+    "pixiv_id",
+    "pool",
+    "pool_count",
+    "pools",
+    "random",
+    "rating",
+    "ratio",
+    "replacement_count",
+    "replacements",
+    "score",
+    "search",
+    "series_pool_count",
+    "series_pools",
+    "source",
+    "status",
+    "tagcount",
+    "unaliased",
+    "unvoted",
+    "upvote",
+    "upvoter",
+    "upvotes",
+    "user",
+    "vote",
+    "voter",
+    "width",
+];
+
+/// whether the search term is a metatag (e.g. `rating:g`, `-order:score`)
+pub fn is_metatag(term: &str) -> bool {
+    term.trim_start_matches(['-', '~'])
+        .split_once(':')
+        .is_some_and(|(key, _)| METATAGS.contains(&key.to_lowercase().as_str()))
+}
+
 /// filtering using one or more conditions
 #[derive(Debug, Clone)]
 pub enum Range<T: Display> {
@@ -249,4 +361,15 @@ mod test {
             "1girl solo rating:g,s filetype:jpg,png score:50..100 date:2000-01-23..<2024-10-20 order:score_desc"
         );
     }
+
+    #[test]
+    fn test_is_metatag() {
+        assert!(is_metatag("rating:g"));
+        assert!(is_metatag("-Order:score"));
+        assert!(is_metatag("ordfav:username"));
+        assert!(is_metatag("favgroup:1"));
+        assert!(is_metatag("chartags:>2"));
+        assert!(!is_metatag("re:zero"));
+        assert!(!is_metatag("cat_ears"));
+    }
 }
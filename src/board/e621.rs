@@ -0,0 +1,180 @@
+pub mod response;
+pub mod search;
+
+use super::{e926, found, BoardApi, BoardEndpoint, BoardQuery};
+use crate::client::{Client, RateLimiter};
+use crate::error::Result;
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+pub const HOST: &str = "https://e621.net";
+
+/// max number of posts per page
+pub const MAX_LIMIT: i64 = 320;
+
+/// User-Agent in the format required by the API, naming the project, the user and the board
+pub fn user_agent(board: &str, username: Option<&str>) -> String {
+    format!(
+        "booru-rs/{} (by {} on {})",
+        env!("CARGO_PKG_VERSION"),
+        username.unwrap_or("anonymous"),
+        board
+    )
+}
+
+// -- re-exports
+
+pub use search::SearchTagsBuilder;
+
+// -- commmon types --
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum Rating {
+    #[serde(rename = "s")]
+    Safe,
+    #[serde(rename = "q")]
+    Questionable,
+    #[serde(rename = "e")]
+    Explicit,
+}
+
+impl fmt::Display for Rating {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Rating::Safe => "s",
+            Rating::Questionable => "q",
+            Rating::Explicit => "e",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+// -- e621 types --
+
+/// e621 api endpoint
+#[derive(Debug, Clone)]
+pub enum Endpoint {
+    Posts,
+    Post(i64),
+}
+
+impl BoardEndpoint for Endpoint {
+    fn path(&self) -> String {
+        match self {
+            Endpoint::Posts => "/posts.json".to_string(),
+            Endpoint::Post(id) => format!("/posts/{}.json", id),
+        }
+    }
+}
+
+/// e621 api query, which supports `page=b<id>` and `page=a<id>` like danbooru
+#[derive(Debug, Clone, Default)]
+pub struct Query(Vec<(String, String)>);
+
+impl Query {
+    pub fn new() -> Self {
+        Query(Vec::new())
+    }
+
+    /// request parameters for /posts.json
+    pub fn posts(tags: &str) -> Self {
+        let mut query = Query::new();
+        query.insert("tags", tags);
+        query
+    }
+}
+
+impl BoardQuery for Query {
+    fn pairs(&self) -> Vec<(String, String)> {
+        self.0.clone()
+    }
+
+    fn insert<T: ToString, K: ToString>(&mut self, key: T, value: K) {
+        self.0.push((key.to_string(), value.to_string()));
+    }
+}
+
+/// e621 engine and the boards running it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum E621 {
+    #[default]
+    E621,
+    E926,
+}
+
+impl BoardApi for E621 {
+    type Endpoint = Endpoint;
+    type Query = Query;
+    type SearchBuilder = SearchTagsBuilder;
+    type Post = response::Post;
+    type Posts = response::Posts;
+
+    const MAX_LIMIT: i64 = MAX_LIMIT;
+    const AUTH_PARAMS: (&'static str, &'static str) = ("login", "api_key");
+    const BASIC_AUTH: bool = true;
+
+    fn host(&self) -> &str {
+        match self {
+            E621::E621 => HOST,
+            E621::E926 => e926::HOST,
+        }
+    }
+
+    fn name(&self) -> &str {
+        match self {
+            E621::E621 => "e621",
+            E621::E926 => "e926",
+        }
+    }
+
+    /// e621 blocks generic user agents
+    fn user_agent(&self, username: Option<&str>) -> String {
+        user_agent(self.name(), username)
+    }
+
+    fn default_rate_limiter(&self) -> RateLimiter {
+        RateLimiter::e621()
+    }
+
+    fn posts(tags: &str) -> (Endpoint, Query) {
+        (Endpoint::Posts, Query::posts(tags))
+    }
+
+    async fn get_post(client: &Client<Self>, id: i64) -> Result<Option<response::Post>> {
+        let url = client.compose(Endpoint::Post(id), Query::new())?;
+        found(client.fetch(url, Method::GET).await)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::Page;
+
+    #[test]
+    fn test_endpoint_path() {
+        assert_eq!(Endpoint::Posts.path(), "/posts.json");
+        assert_eq!(Endpoint::Post(1234).path(), "/posts/1234.json");
+    }
+
+    #[test]
+    fn test_query_page() {
+        let mut query = Query::posts("wolf rating:s");
+        query.limit(MAX_LIMIT);
+        query.page(Page::Before(1234));
+        assert_eq!(
+            query.to_string(),
+            "tags=wolf+rating%3As&limit=320&page=b1234"
+        );
+    }
+
+    #[test]
+    fn test_user_agent() {
+        assert!(user_agent("e621", Some("example")).ends_with(" (by example on e621)"));
+        assert!(user_agent("e621", None).starts_with("booru-rs/"));
+        assert!(E621::E926
+            .user_agent(None)
+            .ends_with(" (by anonymous on e926)"));
+    }
+}
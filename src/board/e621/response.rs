@@ -0,0 +1,4 @@
+pub mod post;
+
+// -- re-exports
+pub use post::{Post, PostTags, Posts};
@@ -0,0 +1,178 @@
+use crate::board::e621::Rating;
+use crate::board::BoardResponse;
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+
+/// response type for /posts/{id}
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Post {
+    pub id: i64,
+    pub created_at: String,
+    pub updated_at: Option<String>,
+    pub file: File,
+    pub preview: Preview,
+    pub sample: Sample,
+    pub score: Score,
+    pub tags: PostTags,
+    pub rating: Rating,
+    pub fav_count: i64,
+    pub sources: Vec<String>,
+    /// ids of the pools containing the post
+    pub pools: Vec<i64>,
+    pub relationships: Relationships,
+    pub flags: Flags,
+    pub uploader_id: i64,
+    pub approver_id: Option<i64>,
+    pub description: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct File {
+    pub width: i64,
+    pub height: i64,
+    pub ext: String,
+    pub size: i64,
+    pub md5: String,
+    /// null for deleted posts and for posts hidden from anonymous users
+    pub url: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Preview {
+    pub width: i64,
+    pub height: i64,
+    pub url: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Sample {
+    pub has: bool,
+    pub width: i64,
+    pub height: i64,
+    pub url: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Score {
+    pub up: i64,
+    /// negative
+    pub down: i64,
+    pub total: i64,
+}
+
+/// tags grouped by category
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PostTags {
+    pub general: Vec<String>,
+    pub artist: Vec<String>,
+    pub contributor: Vec<String>,
+    pub copyright: Vec<String>,
+    pub character: Vec<String>,
+    pub species: Vec<String>,
+    pub invalid: Vec<String>,
+    pub meta: Vec<String>,
+    pub lore: Vec<String>,
+}
+
+impl PostTags {
+    /// all tags regardless of the category
+    pub fn all(&self) -> impl Iterator<Item = &str> {
+        [
+            &self.general,
+            &self.artist,
+            &self.contributor,
+            &self.copyright,
+            &self.character,
+            &self.species,
+            &self.invalid,
+            &self.meta,
+            &self.lore,
+        ]
+        .into_iter()
+        .flatten()
+        .map(String::as_str)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Relationships {
+    pub parent_id: Option<i64>,
+    pub has_children: bool,
+    pub has_active_children: bool,
+    pub children: Vec<i64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Flags {
+    pub pending: bool,
+    pub flagged: bool,
+    pub note_locked: bool,
+    pub status_locked: bool,
+    pub rating_locked: bool,
+    pub deleted: bool,
+}
+
+/// response type for /posts
+pub type Posts = Vec<Post>;
+
+/// /posts.json wraps posts in an object
+#[derive(Debug, Deserialize)]
+struct PostsEnvelope {
+    posts: Posts,
+}
+
+/// /posts/{id}.json wraps the post in an object
+#[derive(Debug, Deserialize)]
+struct PostEnvelope {
+    post: Post,
+}
+
+impl BoardResponse for Posts {
+    fn from_str(s: &str) -> Result<Self> {
+        let envelope: PostsEnvelope = serde_json::from_str(s).map_err(|e| Error::decode(e, s))?;
+        Ok(envelope.posts)
+    }
+}
+
+impl BoardResponse for Post {
+    fn from_str(s: &str) -> Result<Self> {
+        let envelope: PostEnvelope = serde_json::from_str(s).map_err(|e| Error::decode(e, s))?;
+        Ok(envelope.post)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_posts() {
+        let posts =
+            Posts::from_str(include_str!("../../../../tests/fixtures/e621/posts.json")).unwrap();
+
+        assert_eq!(posts.len(), 2);
+        let post = &posts[0];
+        assert_eq!(post.rating, Rating::Safe);
+        assert_eq!(post.score.total, 95);
+        assert_eq!(post.tags.species, vec!["canine", "wolf"]);
+        assert_eq!(post.tags.all().count(), 7);
+        assert!(post.file.url.as_deref().unwrap().ends_with(".png"));
+
+        assert_eq!(posts[1].file.url, None);
+        assert_eq!(posts[1].relationships.parent_id, Some(4800001));
+    }
+
+    #[test]
+    fn test_parse_post() {
+        let body = format!(
+            r#"{{"post":{}}}"#,
+            serde_json::to_string(
+                &Posts::from_str(include_str!("../../../../tests/fixtures/e621/posts.json"))
+                    .unwrap()[0]
+            )
+            .unwrap()
+        );
+        assert_eq!(Post::from_str(&body).unwrap().id, 4800002);
+    }
+}
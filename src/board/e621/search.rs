@@ -0,0 +1,62 @@
+use std::fmt;
+
+use crate::board::search::SimpleSearchTagsBuilder;
+use crate::board::BoardSearchTagsBuilder;
+
+use super::{Rating, E621};
+
+/// sort order of posts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Order {
+    /// oldest first
+    Id,
+    /// newest first (default)
+    IdDesc,
+    Score,
+    ScoreAsc,
+    Favcount,
+    Random,
+}
+
+impl fmt::Display for Order {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Order::Id => "id",
+            Order::IdDesc => "id_desc",
+            Order::Score => "score",
+            Order::ScoreAsc => "score_asc",
+            Order::Favcount => "favcount",
+            Order::Random => "random",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+/// e621 search tags builder
+pub type SearchTagsBuilder = SimpleSearchTagsBuilder<E621>;
+
+impl SearchTagsBuilder {
+    /// set rating metatag
+    pub fn rating(&mut self, rating: Rating) {
+        self.set_metatag("rating", vec![rating.to_string()]);
+    }
+
+    /// set order
+    pub fn order(&mut self, order: Order) {
+        self.set_metatag("order", vec![order.to_string()]);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_search_tags_builder() {
+        let mut builder = SearchTagsBuilder::new();
+        builder.rating(Rating::Safe);
+        builder.order(Order::Favcount);
+
+        assert_eq!(builder.build(), "rating:s order:favcount");
+    }
+}
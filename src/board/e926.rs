@@ -0,0 +1,7 @@
+use super::e621;
+
+pub const HOST: &str = "https://e926.net";
+
+// -- re-exports
+
+pub use e621::*;
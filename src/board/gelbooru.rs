@@ -0,0 +1,211 @@
+pub mod response;
+pub mod search;
+
+use super::{BoardApi, BoardEndpoint, BoardQuery, Page};
+use crate::client::Client;
+use crate::error::Result;
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+pub const HOST: &str = "https://gelbooru.com";
+
+/// max number of posts per page
+pub const MAX_LIMIT: i64 = 100;
+
+// -- re-exports
+
+pub use search::SearchTagsBuilder;
+
+// -- commmon types --
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Rating {
+    /// `safe` on sites which have not adopted the new ratings
+    #[serde(alias = "safe")]
+    General,
+    Sensitive,
+    Questionable,
+    Explicit,
+}
+
+impl fmt::Display for Rating {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Rating::General => "general",
+            Rating::Sensitive => "sensitive",
+            Rating::Questionable => "questionable",
+            Rating::Explicit => "explicit",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+// -- gelbooru types --
+
+/// gelbooru dapi endpoint
+#[derive(Debug, Clone)]
+pub enum Endpoint {
+    Posts,
+}
+
+impl BoardEndpoint for Endpoint {
+    fn path(&self) -> String {
+        match self {
+            Endpoint::Posts => "/index.php?page=dapi&s=post&q=index&json=1".to_string(),
+        }
+    }
+}
+
+/// gelbooru dapi query
+#[derive(Debug, Clone, Default)]
+pub struct Query(Vec<(String, String)>);
+
+impl Query {
+    pub fn new() -> Self {
+        Query(Vec::new())
+    }
+
+    /// request parameters for posts
+    pub fn posts(tags: &str) -> Self {
+        let mut query = Query::new();
+        query.insert("tags", tags);
+        query
+    }
+
+    /// request parameters for the post
+    pub fn post(id: i64) -> Self {
+        let mut query = Query::new();
+        query.insert("id", id);
+        query
+    }
+
+    /// append to the search tags
+    fn append_tags(&mut self, tags: &str) {
+        match self.0.iter_mut().find(|(k, _)| k == "tags") {
+            Some((_, v)) if !v.trim().is_empty() => *v = format!("{} {}", v.trim_end(), tags),
+            Some((_, v)) => *v = tags.to_string(),
+            None => self.insert("tags", tags),
+        }
+    }
+}
+
+impl BoardQuery for Query {
+    fn pairs(&self) -> Vec<(String, String)> {
+        self.0.clone()
+    }
+
+    fn insert<T: ToString, K: ToString>(&mut self, key: T, value: K) {
+        self.0.push((key.to_string(), value.to_string()));
+    }
+
+    fn page<P: Into<Page>>(&mut self, page: P) {
+        match page.into() {
+            // "page" is taken by the dapi, and "pid" starts from 0
+            Page::Number(page) => self.insert("pid", (page - 1).max(0)),
+            // no cursor pages, so the id range is searched instead
+            Page::Before(id) => self.append_tags(&format!("id:<{}", id)),
+            Page::After(id) => self.append_tags(&format!("id:>{} sort:id:asc", id)),
+        }
+    }
+}
+
+/// Gelbooru 0.2 engine, with the host of the board running it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Gelbooru {
+    host: String,
+}
+
+impl Gelbooru {
+    /// another board running the engine (e.g. `https://safebooru.org`)
+    pub fn new(host: &str) -> Self {
+        Gelbooru {
+            host: host.to_string(),
+        }
+    }
+}
+
+impl Default for Gelbooru {
+    /// gelbooru.com
+    fn default() -> Self {
+        Gelbooru::new(HOST)
+    }
+}
+
+impl BoardApi for Gelbooru {
+    type Endpoint = Endpoint;
+    type Query = Query;
+    type SearchBuilder = SearchTagsBuilder;
+    type Post = response::Post;
+    type Posts = response::Posts;
+
+    const MAX_LIMIT: i64 = MAX_LIMIT;
+    const AUTH_PARAMS: (&'static str, &'static str) = ("user_id", "api_key");
+    const BASIC_AUTH: bool = false;
+
+    fn host(&self) -> &str {
+        &self.host
+    }
+
+    /// `gelbooru` for gelbooru.com, the host without the scheme for other boards
+    fn name(&self) -> &str {
+        match self.host.as_str() {
+            HOST => "gelbooru",
+            host => host.split_once("://").map_or(host, |(_, host)| host),
+        }
+    }
+
+    fn posts(tags: &str) -> (Endpoint, Query) {
+        (Endpoint::Posts, Query::posts(tags))
+    }
+
+    async fn get_post(client: &Client<Self>, id: i64) -> Result<Option<response::Post>> {
+        let url = client.compose(Endpoint::Posts, Query::post(id))?;
+        let posts = client.fetch::<response::Posts>(url, Method::GET).await?;
+        Ok(posts.into_iter().next())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_name() {
+        assert_eq!(Gelbooru::default().name(), "gelbooru");
+        assert_eq!(
+            Gelbooru::new("https://safebooru.org").name(),
+            "safebooru.org"
+        );
+    }
+
+    #[test]
+    fn test_endpoint_path() {
+        assert_eq!(
+            Endpoint::Posts.path(),
+            "/index.php?page=dapi&s=post&q=index&json=1"
+        );
+    }
+
+    #[test]
+    fn test_query_page() {
+        let mut query = Query::posts("cat_ears");
+        query.limit(MAX_LIMIT);
+        query.page(1);
+        assert_eq!(query.to_string(), "tags=cat_ears&limit=100&pid=0");
+
+        let mut query = Query::posts("cat_ears");
+        query.page(Page::Before(1234));
+        assert_eq!(query.to_string(), "tags=cat_ears+id%3A%3C1234");
+    }
+
+    #[test]
+    fn test_rating() {
+        assert_eq!(
+            serde_json::from_str::<Rating>(r#""safe""#).unwrap(),
+            Rating::General
+        );
+        assert_eq!(Rating::Sensitive.to_string(), "sensitive");
+    }
+}
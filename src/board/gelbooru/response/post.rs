@@ -0,0 +1,126 @@
+use crate::board::gelbooru::Rating;
+use crate::board::BoardResponse;
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+
+/// response type for posts
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Post {
+    pub id: i64,
+    /// e.g. `Sat Aug 03 12:34:56 -0500 2024`, missing on older sites
+    pub created_at: Option<String>,
+    /// null on older sites
+    pub score: Option<i64>,
+    pub rating: Rating,
+    #[serde(default)]
+    pub source: String,
+    /// name of the uploader
+    pub owner: String,
+    pub creator_id: Option<i64>,
+    /// 0 or null without parents
+    pub parent_id: Option<i64>,
+
+    // tag
+    /// space separated tags, without categories
+    pub tags: String,
+
+    // file
+    #[serde(alias = "hash")]
+    pub md5: String,
+    pub width: i64,
+    pub height: i64,
+    /// directory of the image under `/images`
+    pub directory: String,
+    /// file name of the image
+    pub image: String,
+
+    // url, missing on older sites
+    pub file_url: Option<String>,
+    pub sample_url: Option<String>,
+    pub preview_url: Option<String>,
+
+    // status
+    pub status: Option<String>,
+}
+
+impl Post {
+    pub fn tags(&self) -> impl Iterator<Item = &str> {
+        self.tags.split_whitespace()
+    }
+
+    pub fn parent(&self) -> Option<i64> {
+        self.parent_id.filter(|id| *id > 0)
+    }
+
+    /// path of the image, for sites which do not return `file_url`
+    pub fn image_path(&self) -> String {
+        format!("/images/{}/{}", self.directory, self.image)
+    }
+}
+
+/// response type for posts
+pub type Posts = Vec<Post>;
+
+/// gelbooru.com wraps posts with the paging attributes
+#[derive(Debug, Deserialize)]
+struct Envelope {
+    #[serde(default)]
+    post: Posts,
+}
+
+impl BoardResponse for Posts {
+    fn from_str(s: &str) -> Result<Self> {
+        // older sites return an empty body for no posts, and a bare array otherwise
+        let trimmed = s.trim();
+        if trimmed.is_empty() {
+            return Ok(vec![]);
+        }
+        if trimmed.starts_with('[') {
+            let posts: Posts = serde_json::from_str(s).map_err(|e| Error::decode(e, s))?;
+            return Ok(posts);
+        }
+        let envelope: Envelope = serde_json::from_str(s).map_err(|e| Error::decode(e, s))?;
+        Ok(envelope.post)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_posts() {
+        let posts = Posts::from_str(include_str!(
+            "../../../../tests/fixtures/gelbooru/posts.json"
+        ))
+        .unwrap();
+
+        assert_eq!(posts.len(), 2);
+        assert_eq!(posts[0].rating, Rating::General);
+        assert_eq!(posts[0].parent(), None);
+        assert!(posts[0].tags().any(|tag| tag == "cat_ears"));
+    }
+
+    #[test]
+    fn test_parse_legacy_posts() {
+        let posts = Posts::from_str(include_str!(
+            "../../../../tests/fixtures/gelbooru/posts_legacy.json"
+        ))
+        .unwrap();
+
+        assert_eq!(posts.len(), 1);
+        assert_eq!(posts[0].rating, Rating::General);
+        assert_eq!(posts[0].file_url, None);
+        assert_eq!(
+            posts[0].image_path(),
+            "/images/1234/0123456789abcdef0123456789abcdef.jpg"
+        );
+
+        assert!(Posts::from_str("").unwrap().is_empty());
+        assert!(
+            Posts::from_str(r#"{"@attributes":{"limit":100,"offset":0,"count":0}}"#)
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
@@ -0,0 +1,60 @@
+use std::fmt;
+
+use crate::board::search::SimpleSearchTagsBuilder;
+use crate::board::BoardSearchTagsBuilder;
+
+use super::{Gelbooru, Rating};
+
+/// sort order of posts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sort {
+    /// newest first (default)
+    Id,
+    /// oldest first
+    IdAsc,
+    Score,
+    Updated,
+    Random,
+}
+
+impl fmt::Display for Sort {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Sort::Id => "id:desc",
+            Sort::IdAsc => "id:asc",
+            Sort::Score => "score:desc",
+            Sort::Updated => "updated:desc",
+            Sort::Random => "random",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+/// gelbooru search tags builder
+pub type SearchTagsBuilder = SimpleSearchTagsBuilder<Gelbooru>;
+
+impl SearchTagsBuilder {
+    /// set rating metatag, gelbooru accepts only one rating
+    pub fn rating(&mut self, rating: Rating) {
+        self.set_metatag("rating", vec![rating.to_string()]);
+    }
+
+    /// set sort order
+    pub fn sort(&mut self, sort: Sort) {
+        self.set_metatag("sort", vec![sort.to_string()]);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_search_tags_builder() {
+        let mut builder = SearchTagsBuilder::new();
+        builder.rating(Rating::General);
+        builder.sort(Sort::Score);
+
+        assert_eq!(builder.build(), "rating:general sort:score:desc");
+    }
+}
@@ -0,0 +1,240 @@
+pub mod response;
+pub mod search;
+
+use super::{konachan, yandere, BoardApi, BoardEndpoint, BoardQuery, Page};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// max number of posts per page
+pub const MAX_LIMIT: i64 = 100;
+
+// -- re-exports
+
+pub use search::SearchTagsBuilder;
+
+// -- commmon types --
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum Rating {
+    #[serde(rename = "s")]
+    Safe,
+    #[serde(rename = "q")]
+    Questionable,
+    #[serde(rename = "e")]
+    Explicit,
+}
+
+impl fmt::Display for Rating {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Rating::Safe => "s",
+            Rating::Questionable => "q",
+            Rating::Explicit => "e",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+/// sort order of /tag.json
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TagOrder {
+    Name,
+    Date,
+    Count,
+}
+
+impl fmt::Display for TagOrder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            TagOrder::Name => "name",
+            TagOrder::Date => "date",
+            TagOrder::Count => "count",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+// -- moebooru types --
+
+/// moebooru api endpoint
+#[derive(Debug, Clone)]
+pub enum Endpoint {
+    Posts,
+    Tags,
+    Pool(i64),
+}
+
+impl BoardEndpoint for Endpoint {
+    fn path(&self) -> String {
+        match self {
+            Endpoint::Posts => "/post.json".to_string(),
+            Endpoint::Tags => "/tag.json".to_string(),
+            Endpoint::Pool(id) => format!("/pool/show/{}.json", id),
+        }
+    }
+}
+
+/// moebooru api query
+#[derive(Debug, Clone, Default)]
+pub struct Query(Vec<(String, String)>);
+
+impl Query {
+    pub fn new() -> Self {
+        Query(Vec::new())
+    }
+
+    /// request parameters for /post.json
+    pub fn posts(tags: &str) -> Self {
+        let mut query = Query::new();
+        query.insert("tags", tags);
+        query
+    }
+
+    /// request parameters for /tag.json, `*` matches any characters
+    pub fn tags(name_pattern: &str) -> Self {
+        let mut query = Query::new();
+        query.insert("name", name_pattern);
+        query
+    }
+
+    /// set sort order of tags
+    pub fn tag_order(&mut self, order: TagOrder) {
+        self.insert("order", order);
+    }
+
+    /// append to the search tags
+    fn append_tags(&mut self, tags: &str) {
+        match self.0.iter_mut().find(|(k, _)| k == "tags") {
+            Some((_, v)) if !v.trim().is_empty() => *v = format!("{} {}", v.trim_end(), tags),
+            Some((_, v)) => *v = tags.to_string(),
+            None => self.insert("tags", tags),
+        }
+    }
+
+    /// remove the `order:` metatags from the search tags
+    fn remove_order(&mut self) {
+        if let Some((_, v)) = self.0.iter_mut().find(|(k, _)| k == "tags") {
+            *v = v
+                .split_whitespace()
+                .filter(|tag| !tag.to_lowercase().starts_with("order:"))
+                .collect::<Vec<_>>()
+                .join(" ");
+        }
+    }
+}
+
+impl BoardQuery for Query {
+    fn pairs(&self) -> Vec<(String, String)> {
+        self.0.clone()
+    }
+
+    fn insert<T: ToString, K: ToString>(&mut self, key: T, value: K) {
+        self.0.push((key.to_string(), value.to_string()));
+    }
+
+    fn page<P: Into<Page>>(&mut self, page: P) {
+        match page.into() {
+            Page::Number(page) => self.insert("page", page),
+            // moebooru has no cursor pages, so the id range is searched instead,
+            // in the order of the ids or the pages skip posts
+            Page::Before(id) => {
+                self.remove_order();
+                self.append_tags(&format!("id:<{} order:id_desc", id));
+            }
+            Page::After(id) => {
+                self.remove_order();
+                self.append_tags(&format!("id:>{} order:id", id));
+            }
+        }
+    }
+}
+
+/// Moebooru engine and the boards running it
+///
+/// it has no endpoint of a single post, so `get_post` searches `id:<id>`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Moebooru {
+    #[default]
+    Yandere,
+    Konachan,
+}
+
+impl BoardApi for Moebooru {
+    type Endpoint = Endpoint;
+    type Query = Query;
+    type SearchBuilder = SearchTagsBuilder;
+    type Post = response::Post;
+    type Posts = response::Posts;
+
+    const MAX_LIMIT: i64 = MAX_LIMIT;
+    const AUTH_PARAMS: (&'static str, &'static str) = ("login", "password_hash");
+    const BASIC_AUTH: bool = false;
+
+    fn host(&self) -> &str {
+        match self {
+            Moebooru::Yandere => yandere::HOST,
+            Moebooru::Konachan => konachan::HOST,
+        }
+    }
+
+    fn name(&self) -> &str {
+        match self {
+            Moebooru::Yandere => "yandere",
+            Moebooru::Konachan => "konachan",
+        }
+    }
+
+    fn posts(tags: &str) -> (Endpoint, Query) {
+        (Endpoint::Posts, Query::posts(tags))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_endpoint_path() {
+        assert_eq!(Endpoint::Posts.path(), "/post.json");
+        assert_eq!(Endpoint::Tags.path(), "/tag.json");
+        assert_eq!(Endpoint::Pool(1234).path(), "/pool/show/1234.json");
+    }
+
+    #[test]
+    fn test_query_page() {
+        let mut query = Query::posts("landscape");
+        query.limit(MAX_LIMIT);
+        query.page(2);
+        assert_eq!(query.to_string(), "tags=landscape&limit=100&page=2");
+
+        let mut query = Query::posts("landscape");
+        query.page(Page::Before(1234));
+        assert_eq!(
+            query.to_string(),
+            "tags=landscape+id%3A%3C1234+order%3Aid_desc"
+        );
+
+        let mut query = Query::posts("landscape order:score");
+        query.page(Page::Before(1234));
+        assert_eq!(
+            query.to_string(),
+            "tags=landscape+id%3A%3C1234+order%3Aid_desc"
+        );
+
+        let mut query = Query::new();
+        query.page(Page::After(1234));
+        assert_eq!(query.to_string(), "tags=id%3A%3E1234+order%3Aid");
+
+        let mut query = Query::posts("landscape order:score");
+        query.page(Page::After(1234));
+        assert_eq!(query.to_string(), "tags=landscape+id%3A%3E1234+order%3Aid");
+    }
+
+    #[test]
+    fn test_query_tags() {
+        let mut query = Query::tags("cat*");
+        query.tag_order(TagOrder::Count);
+        query.limit(10);
+        assert_eq!(query.to_string(), "name=cat*&order=count&limit=10");
+    }
+}
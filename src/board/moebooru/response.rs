@@ -0,0 +1,8 @@
+pub mod pool;
+pub mod post;
+pub mod tag;
+
+// -- re-exports
+pub use pool::Pool;
+pub use post::{Post, Posts};
+pub use tag::{Tag, Tags};
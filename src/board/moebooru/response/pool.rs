@@ -0,0 +1,47 @@
+use super::post::Post;
+use crate::board::BoardResponse;
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+
+/// response type for /pool/show/{id}
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Pool {
+    pub id: i64,
+    /// words are separated by underscores
+    pub name: String,
+    pub description: String,
+    pub user_id: i64,
+    pub is_public: bool,
+    pub post_count: i64,
+    pub created_at: String,
+    pub updated_at: String,
+    /// posts in the pool order
+    #[serde(default)]
+    pub posts: Vec<Post>,
+}
+
+impl BoardResponse for Pool {
+    fn from_str(s: &str) -> Result<Self> {
+        let pool: Pool = serde_json::from_str(s).map_err(|e| Error::decode(e, s))?;
+        Ok(pool)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_pool() {
+        let pool = Pool::from_str(include_str!(
+            "../../../../tests/fixtures/moebooru/pool.json"
+        ))
+        .unwrap();
+
+        assert_eq!(pool.post_count, 2);
+        assert_eq!(
+            pool.posts.iter().map(|p| p.id).collect::<Vec<_>>(),
+            vec![1100001, 1100002]
+        );
+    }
+}
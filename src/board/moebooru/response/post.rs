@@ -0,0 +1,89 @@
+use crate::board::moebooru::Rating;
+use crate::board::BoardResponse;
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+
+/// response type for /post
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Post {
+    pub id: i64,
+    /// unix time
+    pub created_at: i64,
+    pub updated_at: Option<i64>,
+    pub creator_id: Option<i64>,
+    pub approver_id: Option<i64>,
+    /// name of the uploader
+    pub author: String,
+
+    // score
+    pub score: i64,
+    pub source: String,
+    pub rating: Rating,
+
+    // tag
+    /// space separated tags, without categories
+    pub tags: String,
+
+    // file
+    pub md5: String,
+    pub file_size: i64,
+    /// missing on old posts
+    pub file_ext: Option<String>,
+    pub width: i64,
+    pub height: i64,
+
+    // url, missing if posts are deleted or hidden
+    pub file_url: Option<String>,
+    pub sample_url: Option<String>,
+    pub sample_width: i64,
+    pub sample_height: i64,
+    pub jpeg_url: Option<String>,
+    pub jpeg_width: i64,
+    pub jpeg_height: i64,
+    pub preview_url: Option<String>,
+
+    // relation
+    pub parent_id: Option<i64>,
+    pub has_children: bool,
+
+    // status
+    /// active, flagged, pending or deleted
+    pub status: String,
+    pub is_shown_in_index: bool,
+    #[serde(default)]
+    pub is_held: bool,
+}
+
+impl Post {
+    pub fn tags(&self) -> impl Iterator<Item = &str> {
+        self.tags.split_whitespace()
+    }
+}
+
+/// response type for /post
+pub type Posts = Vec<Post>;
+
+impl BoardResponse for Posts {
+    fn from_str(s: &str) -> Result<Self> {
+        let posts: Posts = serde_json::from_str(s).map_err(|e| Error::decode(e, s))?;
+        Ok(posts)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_posts() {
+        let posts = Posts::from_str(include_str!(
+            "../../../../tests/fixtures/moebooru/posts.json"
+        ))
+        .unwrap();
+
+        assert_eq!(posts.len(), 2);
+        assert_eq!(posts[0].rating, Rating::Safe);
+        assert!(posts[0].tags().any(|tag| tag == "landscape"));
+        assert_eq!(posts[1].parent_id, Some(1100001));
+    }
+}
@@ -0,0 +1,42 @@
+use crate::board::BoardResponse;
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+
+/// response type for /tag
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Tag {
+    pub id: i64,
+    pub name: String,
+    pub count: i64,
+    /// 0: general, 1: artist, 3: copyright, 4: character, and the rest differ between boards
+    #[serde(rename = "type")]
+    pub tag_type: u8,
+    pub ambiguous: bool,
+}
+
+/// response type for /tag
+pub type Tags = Vec<Tag>;
+
+impl BoardResponse for Tags {
+    fn from_str(s: &str) -> Result<Self> {
+        let tags: Tags = serde_json::from_str(s).map_err(|e| Error::decode(e, s))?;
+        Ok(tags)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_tags() {
+        let tags = Tags::from_str(include_str!(
+            "../../../../tests/fixtures/moebooru/tags.json"
+        ))
+        .unwrap();
+
+        assert_eq!(tags.len(), 2);
+        assert_eq!(tags[0].name, "landscape");
+        assert_eq!(tags[0].tag_type, 0);
+    }
+}
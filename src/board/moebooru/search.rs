@@ -0,0 +1,68 @@
+use std::fmt;
+
+use crate::board::search::SimpleSearchTagsBuilder;
+use crate::board::BoardSearchTagsBuilder;
+
+use super::{Moebooru, Rating};
+
+/// sort order of posts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Order {
+    /// oldest first
+    Id,
+    /// newest first (default)
+    IdDesc,
+    Score,
+    ScoreAsc,
+    Mpixels,
+    MpixelsAsc,
+    Landscape,
+    Portrait,
+    Random,
+}
+
+impl fmt::Display for Order {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Order::Id => "id",
+            Order::IdDesc => "id_desc",
+            Order::Score => "score",
+            Order::ScoreAsc => "score_asc",
+            Order::Mpixels => "mpixels",
+            Order::MpixelsAsc => "mpixels_asc",
+            Order::Landscape => "landscape",
+            Order::Portrait => "portrait",
+            Order::Random => "random",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+/// moebooru search tags builder
+pub type SearchTagsBuilder = SimpleSearchTagsBuilder<Moebooru>;
+
+impl SearchTagsBuilder {
+    /// set rating metatag, moebooru accepts only one rating
+    pub fn rating(&mut self, rating: Rating) {
+        self.set_metatag("rating", vec![rating.to_string()]);
+    }
+
+    /// set order
+    pub fn order(&mut self, order: Order) {
+        self.set_metatag("order", vec![order.to_string()]);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_search_tags_builder() {
+        let mut builder = SearchTagsBuilder::new();
+        builder.rating(Rating::Safe);
+        builder.order(Order::Score);
+
+        assert_eq!(builder.build(), "rating:s order:score");
+    }
+}
@@ -0,0 +1,92 @@
+use std::collections::HashMap;
+use std::marker::PhantomData;
+
+use indexmap::IndexMap;
+
+use super::BoardSearchTagsBuilder;
+
+/// search tags builder of boards whose metatags are plain `key:value` terms
+///
+/// `B` is the engine, which adds its own helpers (e.g. `rating`) to the builder
+#[derive(Debug, Clone)]
+pub struct SimpleSearchTagsBuilder<B> {
+    tags: Vec<String>,
+    metatags: IndexMap<String, Vec<String>>,
+    board: PhantomData<fn() -> B>,
+}
+
+impl<B> BoardSearchTagsBuilder for SimpleSearchTagsBuilder<B> {
+    fn new() -> Self {
+        SimpleSearchTagsBuilder {
+            tags: Vec::new(),
+            metatags: IndexMap::new(),
+            board: PhantomData,
+        }
+    }
+
+    fn tags(&self) -> Vec<String> {
+        self.tags.clone()
+    }
+
+    fn metatags(&self) -> HashMap<String, String> {
+        self.metatags
+            .iter()
+            .map(|(k, v)| (k.clone(), v.join(",")))
+            .collect()
+    }
+
+    fn add_tag(&mut self, tag: &str) {
+        self.tags.push(tag.to_string());
+    }
+
+    fn set_metatag(&mut self, key: &str, value: Vec<String>) {
+        self.metatags.insert(key.to_string(), value);
+    }
+
+    fn append_metatag(&mut self, key: &str, value: &str) {
+        if let Some(v) = self.metatags.get_mut(key) {
+            v.push(value.to_string());
+        } else {
+            self.set_metatag(key, vec![value.to_string()]);
+        }
+    }
+
+    fn build(&self) -> String {
+        self.tags
+            .iter()
+            .cloned()
+            .chain(
+                self.metatags
+                    .iter()
+                    .map(|(k, v)| format!("{}:{}", k, v.join(","))),
+            )
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+impl<B> SimpleSearchTagsBuilder<B> {
+    /// set minimum score
+    pub fn score_min(&mut self, score: i32) {
+        self.set_metatag("score", vec![format!(">={}", score)]);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_simple_search_tags_builder() {
+        let mut builder = SimpleSearchTagsBuilder::<()>::new();
+        builder.add_tag("landscape");
+        builder.add_tag("-text");
+        builder.append_metatag("id", "1");
+        builder.append_metatag("id", "2");
+        builder.score_min(20);
+
+        assert_eq!(builder.metatags().get("score").unwrap(), ">=20");
+        assert_eq!(builder.build(), "landscape -text id:1,2 score:>=20");
+        assert_eq!(SimpleSearchTagsBuilder::<()>::new().build(), "");
+    }
+}
@@ -0,0 +1,7 @@
+use super::moebooru;
+
+pub const HOST: &str = "https://yande.re";
+
+// -- re-exports
+
+pub use moebooru::*;
@@ -1,17 +1,46 @@
-use crate::board::{Board, BoardEndpoint, BoardQuery, BoardResponse};
-use anyhow::Result;
+mod api;
+mod builder;
+mod cassette;
+mod rate_limit;
+mod retry;
+mod stream;
+mod upload;
+mod write;
+
+use crate::board::danbooru::Danbooru;
+use crate::board::{BoardApi, BoardEndpoint, BoardQuery, BoardResponse};
+use crate::error::{ApiError, Error, Result};
 use base64::{engine::general_purpose, Engine};
-use reqwest::{
-    header::{self, HeaderMap, HeaderValue},
-    Method, RequestBuilder, Response, Url, Version,
-};
+use reqwest::{Method, RequestBuilder, Response, Url, Version};
 use std::sync::Arc;
 
+// -- re-exports
+
+pub use api::PostTexts;
+pub use builder::{ClientBuilder, HttpVersion, DEFAULT_USER_AGENT};
+pub use cassette::{Cassette, CassetteMode};
+pub use rate_limit::{Quota, RateLimitStats, RateLimiter, RequestKind};
+pub use retry::{parse_retry_after, RetryPolicy};
+pub use stream::{PageMode, PostsStreamOptions};
+pub use upload::UploadSource;
+pub use write::{FormFile, WriteOutcome, WriteRequest};
+
+/// How credentials are sent to the board
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AuthMethod {
+    /// `Authorization: Basic` header
+    #[default]
+    Basic,
+    /// `login` and `api_key` query parameters
+    Query,
+}
+
 /// Auth struct
 #[derive(Debug, Clone)]
 pub struct Auth {
     username: String,
     api_key: String,
+    method: AuthMethod,
 }
 
 impl Auth {
@@ -20,9 +49,28 @@ impl Auth {
         Auth {
             username: username.to_string(),
             api_key: api_key.to_string(),
+            method: AuthMethod::Basic,
         }
     }
 
+    /// Create a new Auth struct sent as query parameters
+    pub fn query(username: &str, api_key: &str) -> Self {
+        Auth {
+            method: AuthMethod::Query,
+            ..Auth::new(username, api_key)
+        }
+    }
+
+    /// Get the username
+    pub fn username(&self) -> &str {
+        &self.username
+    }
+
+    /// Get the method
+    pub fn method(&self) -> AuthMethod {
+        self.method
+    }
+
     /// Get basic auth
     pub fn basic(&self) -> String {
         format!(
@@ -30,95 +78,299 @@ impl Auth {
             general_purpose::STANDARD.encode(&format!("{}:{}", self.username, self.api_key))
         )
     }
+
+    /// Get query parameters
+    pub fn query_pairs(&self) -> Vec<(&str, &str)> {
+        self.query_pairs_for::<Danbooru>()
+    }
+
+    /// Get query parameters named for the engine
+    pub fn query_pairs_for<B: BoardApi>(&self) -> Vec<(&str, &str)> {
+        let (username_key, api_key_key) = B::AUTH_PARAMS;
+        vec![(username_key, &self.username), (api_key_key, &self.api_key)]
+    }
 }
 
-/// Danbooru API Client
+/// API Client of the board `B`, which defaults to Danbooru
 #[derive(Debug, Clone)]
-pub struct Client {
+pub struct Client<B = Danbooru> {
     client: Arc<reqwest::Client>,
-    pub board: Board,
+    retry: RetryPolicy,
+    rate_limiter: Arc<RateLimiter>,
+    http_version: HttpVersion,
+    base_url: Url,
+    auth: Option<Auth>,
+    cassette: Option<Arc<Cassette>>,
+    dry_run: bool,
+    pub board: B,
 }
 
 /// Initialization
-impl Client {
+impl<B: BoardApi> Client<B> {
     /// Create a new Client
-    pub fn new(board: Board, auth: Auth) -> Result<Self> {
-        // create default headers
-        let mut headers: HeaderMap = HeaderMap::new();
-        headers.insert(
-            header::USER_AGENT,
-            HeaderValue::from_static("danboorust client"),
-        );
-        headers.insert(header::AUTHORIZATION, HeaderValue::from_str(&auth.basic())?);
-
-        // get client builder and gen client
-        let client_builder = reqwest::Client::builder()
-            .default_headers(headers)
-            .http2_prior_knowledge();
-
-        #[cfg(feature = "http3")]
-        let client_builder = reqwest::Client::builder()
-            .default_headers(headers)
-            .http3_prior_knowledge();
+    pub fn new(board: B, auth: Auth) -> Result<Self> {
+        Client::builder(board).auth(auth).build()
+    }
 
-        let client = client_builder.build()?;
+    /// Create a new Client without credentials
+    pub fn anonymous(board: B) -> Result<Self> {
+        Client::builder(board).build()
+    }
 
-        Ok(Client {
-            client: Arc::new(client),
-            board,
-        })
+    /// Create a new ClientBuilder
+    pub fn builder(board: B) -> ClientBuilder<B> {
+        ClientBuilder::new(board)
     }
+}
 
+impl Client {
     /// Create a new Danbooru Client
     pub fn danbooru(auth: Auth) -> Result<Self> {
-        Client::new(Board::Danbooru, auth)
+        Client::new(Danbooru::Danbooru, auth)
     }
 
     /// Create a new Safebooru Client
     pub fn safebooru(auth: Auth) -> Result<Self> {
-        Client::new(Board::Safebooru, auth)
+        Client::new(Danbooru::Safebooru, auth)
+    }
+}
+
+/// Configuration
+impl<B> Client<B> {
+    /// Set the retry policy
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Get the retry policy
+    pub fn retry_policy(&self) -> &RetryPolicy {
+        &self.retry
+    }
+
+    /// Set the rate limiter, which is shared with the clones created after this
+    pub fn with_rate_limiter(mut self, rate_limiter: RateLimiter) -> Self {
+        self.rate_limiter = Arc::new(rate_limiter);
+        self
+    }
+
+    /// Get the rate limiter
+    pub fn rate_limiter(&self) -> &RateLimiter {
+        &self.rate_limiter
+    }
+
+    /// Get the base url requests are sent to
+    pub fn base_url(&self) -> &Url {
+        &self.base_url
+    }
+
+    /// Get the credentials
+    pub fn auth(&self) -> Option<&Auth> {
+        self.auth.as_ref()
+    }
+
+    /// Get the cassette responses are recorded to or replayed from
+    pub fn cassette(&self) -> Option<&Cassette> {
+        self.cassette.as_deref()
+    }
+
+    /// Set whether write requests are only described instead of being sent
+    pub fn with_dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// Whether write requests are only described instead of being sent
+    pub fn is_dry_run(&self) -> bool {
+        self.dry_run
     }
 }
 
 /// Methods
-impl Client {
+impl<B: BoardApi> Client<B> {
     /// Compose a url with path
-    fn _compose(&self, path: &str, query: &str) -> Result<Url> {
-        let mut url = Url::parse(self.board.host())?.join(path)?;
-        url.set_query(Some(&query));
+    fn _compose(&self, path: &str, pairs: Vec<(String, String)>) -> Result<Url> {
+        let mut url = self.base_url.join(path.trim_start_matches('/'))?;
+        if !pairs.is_empty() {
+            url.query_pairs_mut().extend_pairs(pairs);
+        }
         Ok(url)
     }
 
     pub fn compose<E: BoardEndpoint, Q: BoardQuery>(&self, endpoint: E, query: Q) -> Result<Url> {
-        self._compose(&endpoint.path(), &query.to_string())
+        self._compose(&endpoint.path(), query.pairs())
     }
 
     /// create request builder
     pub fn request_builder(&self, method: Method, url: Url) -> RequestBuilder {
-        let builder =
-            self.client
-                .clone()
-                .request(method, url)
-                .version(match cfg!(feature = "http3") {
-                    true => Version::HTTP_3,
-                    false => Version::HTTP_2,
-                });
-        builder
+        // don't leak credentials to other hosts (e.g. CDN)
+        let auth = self
+            .auth
+            .as_ref()
+            .filter(|_| url.origin() == self.base_url.origin());
+
+        let mut builder = self.client.request(method, url);
+        builder = match auth {
+            Some(auth) if auth.method == AuthMethod::Basic => {
+                builder.header(reqwest::header::AUTHORIZATION, auth.basic())
+            }
+            Some(auth) => builder.query(&auth.query_pairs_for::<B>()),
+            None => builder,
+        };
+        match self.http_version {
+            HttpVersion::Http2 => builder.version(Version::HTTP_2),
+            #[cfg(feature = "http3")]
+            HttpVersion::Http3 => builder.version(Version::HTTP_3),
+            _ => builder,
+        }
+    }
+
+    /// Send a request, retrying transient failures according to the retry policy
+    pub async fn send(&self, builder: RequestBuilder) -> Result<Response> {
+        // requests with streaming bodies cannot be cloned, so they are sent only once
+        if builder.try_clone().is_none() {
+            let (client, request) = builder.build_split();
+            let request = request?;
+            self.rate_limiter
+                .acquire(RequestKind::from_method(request.method()))
+                .await;
+            return Ok(client.execute(request).await?);
+        }
+        self.send_with(|| builder.try_clone().expect("the body is not a stream"))
+            .await
+    }
+
+    /// Send the request built for each attempt, retrying according to the retry policy
+    async fn send_with<F>(&self, build: F) -> Result<Response>
+    where
+        F: Fn() -> RequestBuilder,
+    {
+        let mut attempt = 0;
+        loop {
+            attempt += 1;
+
+            let (client, request) = build().build_split();
+            let request = request?;
+            let method = request.method().clone();
+            self.rate_limiter
+                .acquire(RequestKind::from_method(&method))
+                .await;
+            let last_attempt = attempt >= self.retry.max_attempts;
+
+            match client.execute(request).await {
+                Ok(res)
+                    if !last_attempt && self.retry.should_retry_status(&method, res.status()) =>
+                {
+                    let delay = self.retry.delay_for_response(attempt, &res);
+                    tokio::time::sleep(delay).await;
+                }
+                Err(err) if !last_attempt && self.retry.should_retry_error(&method, &err) => {
+                    tokio::time::sleep(self.retry.backoff(attempt)).await;
+                }
+                res => return Ok(res?),
+            }
+        }
+    }
+
+    /// Send the request, or replay it from the cassette
+    ///
+    /// the body is built for each attempt, so streamed bodies (e.g. uploaded files) are retried too.
+    /// `key_body` replaces the body in the cassette key when it is streamed
+    async fn dispatch<F>(
+        &self,
+        url: Url,
+        method: Method,
+        with_body: F,
+        key_body: Option<Vec<u8>>,
+    ) -> Result<Response>
+    where
+        F: Fn(RequestBuilder) -> RequestBuilder,
+    {
+        let build = || with_body(self.request_builder(method.clone(), url.clone()));
+        let Some(cassette) = self.cassette.as_deref() else {
+            return self.send_with(build).await;
+        };
+
+        // the body tells apart requests to the same url (e.g. different edits of a post)
+        let body = match key_body {
+            Some(body) => body,
+            None => build()
+                .build()?
+                .body()
+                .and_then(|body| body.as_bytes())
+                .unwrap_or_default()
+                .to_vec(),
+        };
+        match cassette.mode() {
+            CassetteMode::Replay => cassette.replay_response(&method, &url, &body).await,
+            CassetteMode::Record => {
+                let res = self.send_with(build).await?;
+                cassette.record_response(&method, &url, &body, res).await
+            }
+        }
     }
 
     /// Send get request and return response
     pub async fn fetch_raw(&self, url: Url, method: Method) -> Result<Response> {
-        let builder = self.request_builder(method, url);
-        let res = builder.send().await?;
-        Ok(res)
+        self.dispatch(url, method, |builder| builder, None).await
     }
 
     /// Send get request and return response as specified type
     pub async fn fetch<T: BoardResponse>(&self, url: Url, method: Method) -> Result<T> {
         let res = self.fetch_raw(url, method).await?;
-        let text = res.text().await?;
-        let res = T::from_str(&text)?;
-        Ok(res)
+        decode(res).await
+    }
+
+    /// Send request with the urlencoded form body and return response
+    pub async fn submit_raw(
+        &self,
+        url: Url,
+        method: Method,
+        form: &[(String, String)],
+    ) -> Result<Response> {
+        self.dispatch(url, method, |builder| builder.form(form), None)
+            .await
+    }
+
+    /// Send request with the multipart form body and return response
+    pub async fn submit_multipart_raw(
+        &self,
+        url: Url,
+        method: Method,
+        form: &[(String, String)],
+        file: &FormFile,
+    ) -> Result<Response> {
+        self.dispatch(
+            url,
+            method,
+            |builder| builder.multipart(file.multipart(form)),
+            Some(file.cassette_body(form)),
+        )
+        .await
+    }
+
+    /// Send request with the urlencoded form body and return response as specified type
+    pub async fn submit<T: BoardResponse>(
+        &self,
+        url: Url,
+        method: Method,
+        form: &[(String, String)],
+    ) -> Result<T> {
+        let res = self.submit_raw(url, method, form).await?;
+        decode(res).await
+    }
+}
+
+/// check the status and parse the body, preferring the error message of the board
+async fn decode<T: BoardResponse>(res: Response) -> Result<T> {
+    let res = Error::check_response(res).await?;
+    let text = res.text().await?;
+    match T::from_str(&text) {
+        Ok(res) => Ok(res),
+        Err(err) => match ApiError::from_body(&text) {
+            Some(api) => Err(Error::Api(api)),
+            None => Err(err),
+        },
     }
 }
 
@@ -126,8 +378,12 @@ impl Client {
 mod tests {
     use super::*;
 
-    use crate::board::{danbooru, safebooru, BoardSearchTagsBuilder};
-    use crate::test_utils::Env;
+    use crate::board::{
+        danbooru, e621, gelbooru, moebooru, safebooru, yandere, BoardSearchTagsBuilder, Page,
+        WikiBoardApi,
+    };
+    use crate::mock::{fixtures, MockResponse, MockServer};
+    use crate::model;
 
     #[test]
     fn test_auth() {
@@ -136,11 +392,86 @@ mod tests {
         assert_eq!(auth.basic(), "Basic dXNlcm5hbWU6UGFzc1cwcmQh");
     }
 
-    #[tokio::test]
-    async fn test_base_url() {
-        let env = Env::new();
+    #[test]
+    fn test_compose_urlencode() {
+        let client = Client::danbooru(Auth::new("username", "PassW0rd!")).unwrap();
+
+        let mut builder = danbooru::SearchTagsBuilder::new();
+        builder.add_tag("tom_&_jerry");
+        builder.ratings(vec![danbooru::Rating::General]);
+        let mut query = danbooru::Query::posts(&builder.build());
+        query.limit(3);
+
+        let url = client.compose(danbooru::Endpoint::Posts, query).unwrap();
+        assert_eq!(
+            url.as_str(),
+            "https://danbooru.donmai.us/posts.json?tags=tom_%26_jerry+rating%3Ag&limit=3"
+        );
+
+        let url = client
+            .compose(danbooru::Endpoint::Post(1234), danbooru::Query::post())
+            .unwrap();
+        assert_eq!(url.as_str(), "https://danbooru.donmai.us/posts/1234.json");
+    }
+
+    #[test]
+    fn test_custom_base_url() {
+        let client = Client::builder(Danbooru::Danbooru)
+            .base_url("http://localhost:3000/booru")
+            .http_version(HttpVersion::Http1)
+            .user_agent("booru-rs test")
+            .build()
+            .unwrap();
+
+        assert_eq!(client.base_url().as_str(), "http://localhost:3000/booru/");
+
+        let url = client
+            .compose(danbooru::Endpoint::Post(1234), danbooru::Query::post())
+            .unwrap();
+        assert_eq!(url.as_str(), "http://localhost:3000/booru/posts/1234.json");
+    }
+
+    #[test]
+    fn test_query_auth() {
+        let client = Client::builder(Danbooru::Danbooru)
+            .auth(Auth::query("username", "PassW0rd!"))
+            .build()
+            .unwrap();
+
+        let url = client
+            .compose(danbooru::Endpoint::Posts, danbooru::Query::posts("1girl"))
+            .unwrap();
+        let req = client.request_builder(Method::GET, url).build().unwrap();
+        assert_eq!(
+            req.url().as_str(),
+            "https://danbooru.donmai.us/posts.json?tags=1girl&login=username&api_key=PassW0rd%21"
+        );
+        assert!(req.headers().get(reqwest::header::AUTHORIZATION).is_none());
+
+        // not sent to other hosts
+        let url = Url::parse("https://cdn.donmai.us/original/00/00/0000.png").unwrap();
+        let req = client.request_builder(Method::GET, url).build().unwrap();
+        assert_eq!(req.url().query(), None);
+    }
+
+    #[test]
+    fn test_anonymous() {
+        let client = Client::anonymous(Danbooru::Safebooru).unwrap();
+        assert!(client.auth().is_none());
 
-        let auth = Auth::new(&env.username, &env.api_key);
+        let url = client
+            .compose(safebooru::Endpoint::Posts, safebooru::Query::posts("1girl"))
+            .unwrap();
+        let req = client.request_builder(Method::GET, url).build().unwrap();
+        assert_eq!(
+            req.url().as_str(),
+            "https://safebooru.donmai.us/posts.json?tags=1girl"
+        );
+    }
+
+    #[test]
+    fn test_base_url() {
+        let auth = Auth::new("username", "PassW0rd!");
         let client = Client::danbooru(auth.clone()).unwrap();
 
         assert_eq!(client.board.host(), "https://danbooru.donmai.us");
@@ -152,10 +483,17 @@ mod tests {
 
     #[tokio::test]
     async fn test_danbooru_get_posts() {
-        let env = Env::new();
-
-        let auth = Auth::new(&env.username, &env.api_key);
-        let client = Client::danbooru(auth).unwrap();
+        let server = MockServer::start().await;
+        server
+            .mock(Method::GET, "/posts.json")
+            .query("limit", "3")
+            .respond(MockResponse::json(fixtures::POSTS))
+            .mount();
+        let client = server
+            .client_builder(Danbooru::Danbooru)
+            .auth(Auth::new("username", "PassW0rd!"))
+            .build()
+            .unwrap();
 
         let mut query = danbooru::Query::new();
         query.limit(3);
@@ -167,14 +505,46 @@ mod tests {
 
         let posts = danbooru::response::Posts::from_str(&res.text().await.unwrap()).unwrap();
         assert_eq!(posts.len(), 3);
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(
+            requests[0].header("authorization"),
+            Some("Basic dXNlcm5hbWU6UGFzc1cwcmQh")
+        );
+        assert_eq!(requests[0].header("user-agent"), Some(DEFAULT_USER_AGENT));
     }
 
     #[tokio::test]
-    async fn test_danbooru_search_posts() {
-        let env = Env::new();
+    async fn test_basic_auth_other_host() {
+        let server = MockServer::start().await;
+        let cdn = MockServer::start().await;
+        cdn.mock(Method::GET, "/original/00/00/0000.png")
+            .respond(MockResponse::status(200))
+            .mount();
+        let client = server
+            .client_builder(Danbooru::Danbooru)
+            .auth(Auth::new("username", "PassW0rd!"))
+            .build()
+            .unwrap();
+
+        let url = Url::parse(&format!("{}/original/00/00/0000.png", cdn.url())).unwrap();
+        let res = client.fetch_raw(url, Method::GET).await.unwrap();
+        assert!(res.status().is_success());
+
+        let requests = cdn.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].header("authorization"), None);
+    }
 
-        let auth = Auth::new(&env.username, &env.api_key);
-        let client = Client::danbooru(auth).unwrap();
+    #[tokio::test]
+    async fn test_danbooru_search_posts() {
+        let server = MockServer::start().await;
+        server
+            .mock(Method::GET, "/posts.json")
+            .respond(MockResponse::json(fixtures::POSTS))
+            .mount();
+        let client = server.client();
 
         let mut builder = danbooru::SearchTagsBuilder::new();
         builder.add_tag("2girls");
@@ -188,11 +558,11 @@ mod tests {
         query.limit(3);
 
         let url = client.compose(danbooru::Endpoint::Posts, query).unwrap();
-        let res = client.fetch_raw(url, Method::GET).await.unwrap();
-
-        assert!(res.status().is_success());
+        let posts = client
+            .fetch::<danbooru::response::Posts>(url, Method::GET)
+            .await
+            .unwrap();
 
-        let posts = danbooru::response::Posts::from_str(&res.text().await.unwrap()).unwrap();
         assert_eq!(posts.len(), 3);
 
         // check
@@ -203,17 +573,23 @@ mod tests {
             assert!(filetypes.contains(&post.file_ext));
             assert!(post.score >= 10);
         }
+
+        let requests = server.requests();
+        assert_eq!(requests[0].param("tags"), Some(builder.build().as_str()));
+        assert_eq!(requests[0].param("limit"), Some("3"));
     }
 
     #[tokio::test]
     async fn test_safebooru_get_posts() {
-        let env = Env::new();
-
-        let auth = Auth::new(&env.username, &env.api_key);
-        let client = Client::safebooru(auth).unwrap();
+        let server = MockServer::start().await;
+        server
+            .mock(Method::GET, "/posts.json")
+            .respond(MockResponse::json(fixtures::POSTS))
+            .mount();
+        let client = server.client_builder(Danbooru::Safebooru).build().unwrap();
 
         let mut query = safebooru::Query::new();
-        query.limit(10);
+        query.limit(3);
 
         let url = client.compose(safebooru::Endpoint::Posts, query).unwrap();
         let posts = client
@@ -221,6 +597,665 @@ mod tests {
             .await
             .unwrap();
 
-        assert_eq!(posts.len(), 10);
+        assert_eq!(posts.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_retry_too_many_requests() {
+        let server = MockServer::start().await;
+        server
+            .mock(Method::GET, "/posts/8000000.json")
+            .respond(MockResponse::too_many_requests(0))
+            .respond(MockResponse::status(503))
+            .respond(MockResponse::json(fixtures::POST))
+            .mount();
+        let client = server.client();
+
+        let url = client
+            .compose(danbooru::Endpoint::Post(8000000), danbooru::Query::post())
+            .unwrap();
+        let post = client
+            .fetch::<danbooru::response::Post>(url, Method::GET)
+            .await
+            .unwrap();
+
+        assert_eq!(post.id, 8000000);
+        assert_eq!(server.requests().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_error_response() {
+        let server = MockServer::start().await;
+        server
+            .mock(Method::GET, "/posts.json")
+            .respond(MockResponse::too_many_requests(0))
+            .mount();
+        server
+            .mock(Method::GET, "/wiki_pages/cat_ears.json")
+            .respond(MockResponse::json(fixtures::WIKI_PAGE))
+            .mount();
+        let client = server
+            .client_builder(Danbooru::Danbooru)
+            .retry(RetryPolicy::none())
+            .build()
+            .unwrap();
+
+        let url = client
+            .compose(
+                danbooru::Endpoint::WikiPages("cat_ears".to_string()),
+                danbooru::Query::new(),
+            )
+            .unwrap();
+        let wiki_page = client
+            .fetch::<danbooru::response::WikiPage>(url, Method::GET)
+            .await
+            .unwrap();
+        assert_eq!(wiki_page.title, "cat_ears");
+
+        // unknown routes return danbooru's 404 body
+        let url = client
+            .compose(
+                danbooru::Endpoint::WikiPages("not_exist".to_string()),
+                danbooru::Query::new(),
+            )
+            .unwrap();
+        let err = client
+            .fetch::<danbooru::response::WikiPage>(url, Method::GET)
+            .await
+            .unwrap_err();
+        assert!(err.is_not_found());
+
+        let url = client
+            .compose(danbooru::Endpoint::Posts, danbooru::Query::new())
+            .unwrap();
+        let err = client
+            .fetch::<danbooru::response::Posts>(url, Method::GET)
+            .await
+            .unwrap_err();
+        assert!(err.is_rate_limited());
+        assert_eq!(server.requests().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_profile() {
+        let server = MockServer::start().await;
+        server
+            .mock(Method::GET, "/profile.json")
+            .query("login", "username")
+            .respond(MockResponse::json(fixtures::PROFILE))
+            .mount();
+        let client = server
+            .client_builder(Danbooru::Danbooru)
+            .auth(Auth::query("username", "PassW0rd!"))
+            .build()
+            .unwrap();
+
+        let profile = client.profile().await.unwrap();
+        assert!(!profile.is_anonymous());
+
+        let requests = server.requests();
+        assert_eq!(requests[0].param("api_key"), Some("PassW0rd!"));
+        assert_eq!(requests[0].header("authorization"), None);
+    }
+
+    #[tokio::test]
+    async fn test_tag() {
+        let server = MockServer::start().await;
+        server
+            .mock(Method::GET, "/tags.json")
+            .respond(MockResponse::json("[]"))
+            .mount();
+        server
+            .mock(Method::GET, "/tags.json")
+            .query("search[name]", "cat_ears")
+            .respond(MockResponse::json(fixtures::TAGS))
+            .mount();
+        let client = server.client();
+
+        let tag = client.tag("cat_ears").await.unwrap().unwrap();
+        assert_eq!(tag.category, danbooru::TagCategory::General);
+        assert_eq!(tag.post_count, 486123);
+
+        assert!(client.tag("not_exist").await.unwrap().is_none());
+        assert_eq!(server.requests()[0].param("limit"), Some("1"));
+    }
+
+    #[tokio::test]
+    async fn test_tag_graph() {
+        let server = MockServer::start().await;
+        server
+            .mock(Method::GET, "/tag_aliases.json")
+            .respond(MockResponse::json("[]"))
+            .mount();
+        server
+            .mock(Method::GET, "/tag_aliases.json")
+            .query("search[antecedent_name_comma]", "nekomimi,1girl")
+            .respond(MockResponse::json(fixtures::TAG_ALIASES))
+            .mount();
+        server
+            .mock(Method::GET, "/tag_implications.json")
+            .respond(MockResponse::json("[]"))
+            .mount();
+        server
+            .mock(Method::GET, "/tag_implications.json")
+            .query("search[antecedent_name_comma]", "cat_ears,1girl")
+            .respond(MockResponse::json(
+                r#"[{"id":1024,"antecedent_name":"cat_ears","consequent_name":"animal_ears","status":"active","creator_id":null,"approver_id":null,"forum_topic_id":null,"forum_post_id":null,"reason":null,"created_at":"2013-05-01T04:12:33.512-04:00","updated_at":"2020-08-12T16:45:01.003-04:00"}]"#,
+            ))
+            .mount();
+        let client = server.client();
+
+        let graph = client.tag_graph(&["nekomimi", "1girl"]).await.unwrap();
+        assert_eq!(graph.canonical("nekomimi"), "cat_ears");
+        assert!(graph.implies("nekomimi", "animal_ears"));
+
+        // aliases, implications of the tags, implications of animal_ears
+        let requests = server.requests();
+        assert_eq!(requests.len(), 3);
+        assert_eq!(
+            requests[2].param("search[antecedent_name_comma]"),
+            Some("animal_ears")
+        );
+        assert_eq!(requests[2].param("search[status]"), Some("active"));
+    }
+
+    #[tokio::test]
+    async fn test_tag_graph_paging() {
+        let implication = |id: i64, consequent: &str| {
+            format!(
+                r#"{{"id":{},"antecedent_name":"cat_ears","consequent_name":"{}","status":"active","creator_id":null,"approver_id":null,"forum_topic_id":null,"forum_post_id":null,"reason":null,"created_at":"2013-05-01T04:12:33.512-04:00","updated_at":"2020-08-12T16:45:01.003-04:00"}}"#,
+                id, consequent
+            )
+        };
+        let full_page = (0..1000)
+            .map(|i| implication(2000 + i, &format!("tag_{}", i)))
+            .collect::<Vec<_>>()
+            .join(",");
+
+        let server = MockServer::start().await;
+        server
+            .mock(Method::GET, "/tag_aliases.json")
+            .respond(MockResponse::json("[]"))
+            .mount();
+        server
+            .mock(Method::GET, "/tag_implications.json")
+            .respond(MockResponse::json("[]"))
+            .mount();
+        server
+            .mock(Method::GET, "/tag_implications.json")
+            .query("search[antecedent_name_comma]", "cat_ears")
+            .query("page", "1")
+            .respond(MockResponse::json(&format!("[{}]", full_page)))
+            .mount();
+        server
+            .mock(Method::GET, "/tag_implications.json")
+            .query("search[antecedent_name_comma]", "cat_ears")
+            .query("page", "b2000")
+            .respond(MockResponse::json(&format!(
+                "[{}]",
+                implication(1024, "animal_ears")
+            )))
+            .mount();
+        let client = server.client();
+
+        let graph = client.tag_graph(&["cat_ears"]).await.unwrap();
+        assert!(graph.implies("cat_ears", "tag_999"));
+        assert!(graph.implies("cat_ears", "animal_ears"));
+    }
+
+    #[tokio::test]
+    async fn test_full_tag_graph_paging() {
+        let implication = |id: i64| {
+            format!(
+                r#"{{"id":{},"antecedent_name":"tag_{}","consequent_name":"animal_ears","status":"active","creator_id":null,"approver_id":null,"forum_topic_id":null,"forum_post_id":null,"reason":null,"created_at":"2013-05-01T04:12:33.512-04:00","updated_at":"2020-08-12T16:45:01.003-04:00"}}"#,
+                id, id
+            )
+        };
+        let page = |ids: std::ops::Range<i64>| {
+            format!("[{}]", ids.map(implication).collect::<Vec<_>>().join(","))
+        };
+
+        let server = MockServer::start().await;
+        server
+            .mock(Method::GET, "/tag_aliases.json")
+            .respond(MockResponse::json("[]"))
+            .mount();
+        server
+            .mock(Method::GET, "/tag_implications.json")
+            .query("page", "1")
+            .respond(MockResponse::json(&page(2000..3000)))
+            .mount();
+        server
+            .mock(Method::GET, "/tag_implications.json")
+            .query("page", "b2000")
+            .respond(MockResponse::json(&page(1000..1010)))
+            .mount();
+        let client = server.client();
+
+        let graph = client.full_tag_graph().await.unwrap();
+        assert!(graph.implies("tag_2999", "animal_ears"));
+        assert!(graph.implies("tag_1000", "animal_ears"));
+        // the short pages are the last ones, no request for an empty page
+        assert_eq!(server.requests().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_unknown_tags() {
+        let server = MockServer::start().await;
+        server
+            .mock(Method::GET, "/tags.json")
+            .respond(MockResponse::json(fixtures::TAGS))
+            .mount();
+        let client = server.client();
+
+        let mut builder = danbooru::SearchTagsBuilder::new();
+        builder.add_tag("-cat_ears");
+        builder.add_tag("catears");
+        builder.add_tag("cat_*");
+        builder.add_tag("order:score");
+        builder.add_tag("~Cat_Ears");
+        builder.add_tag("ordfav:username");
+        builder.ratings(vec![danbooru::Rating::General]);
+
+        let unknown = client.unknown_tags(&builder).await.unwrap();
+        assert_eq!(unknown, vec!["catears"]);
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(
+            requests[0].param("search[name_comma]"),
+            Some("cat_ears,catears")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_artists_by_url() {
+        let server = MockServer::start().await;
+        server
+            .mock(Method::GET, "/artist_urls.json")
+            .respond(MockResponse::json(fixtures::ARTIST_URLS))
+            .mount();
+        let client = server.client();
+
+        let artists = client
+            .artists_by_url("https://twitter.com/example")
+            .await
+            .unwrap();
+        assert_eq!(artists.len(), 1);
+        assert_eq!(artists[0].name, "example_(artist)");
+
+        let requests = server.requests();
+        assert_eq!(
+            requests[0].param("search[url_matches]"),
+            Some("https://twitter.com/example")
+        );
+        assert!(requests[0].param("only").unwrap().ends_with(",artist"));
+    }
+
+    #[tokio::test]
+    async fn test_pool_posts() {
+        let server = MockServer::start().await;
+        server
+            .mock(Method::GET, "/pools/12345.json")
+            .respond(MockResponse::json(fixtures::POOL))
+            .mount();
+        server
+            .mock(Method::GET, "/posts.json")
+            .respond(MockResponse::json(fixtures::POSTS))
+            .mount();
+        let client = server.client();
+
+        let posts = client.pool_posts(12345).await.unwrap();
+        let ids = posts.iter().map(|p| p.id).collect::<Vec<_>>();
+        assert_eq!(ids, vec![8100002, 8100001, 8100003]);
+
+        let requests = server.requests();
+        assert_eq!(
+            requests[1].param("tags"),
+            Some("id:8100002,8100001,8100003")
+        );
+        assert_eq!(requests[1].param("limit"), Some("3"));
+    }
+
+    #[tokio::test]
+    async fn test_post_texts() {
+        let server = MockServer::start().await;
+        server
+            .mock(Method::GET, "/comments.json")
+            .respond(MockResponse::json(fixtures::COMMENTS))
+            .mount();
+        server
+            .mock(Method::GET, "/notes.json")
+            .respond(MockResponse::json(fixtures::NOTES))
+            .mount();
+        server
+            .mock(Method::GET, "/artist_commentaries.json")
+            .respond(MockResponse::json(fixtures::ARTIST_COMMENTARIES))
+            .mount();
+        let client = server.client();
+
+        let texts = client.post_texts(8000000).await.unwrap();
+        assert_eq!(texts.comments.len(), 1);
+        assert_eq!(texts.notes.len(), 2);
+        let commentary = texts.artist_commentary.unwrap();
+        assert!(commentary.has_translation());
+        assert_eq!(commentary.original_title, "猫耳ミク");
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 3);
+        assert!(requests
+            .iter()
+            .all(|r| r.param("search[post_id]") == Some("8000000")));
+    }
+
+    #[tokio::test]
+    async fn test_post_versions_after() {
+        let server = MockServer::start().await;
+        server
+            .mock(Method::GET, "/post_versions.json")
+            .respond(MockResponse::json(fixtures::POST_VERSIONS))
+            .mount();
+        let client = server.client();
+
+        let versions = client.post_versions_after(90000000).await.unwrap();
+        assert_eq!(
+            versions.iter().map(|v| v.id).collect::<Vec<_>>(),
+            vec![90000001, 90000002]
+        );
+
+        let requests = server.requests();
+        assert_eq!(requests[0].param("page"), Some("a90000000"));
+    }
+
+    #[tokio::test]
+    async fn test_count() {
+        let server = MockServer::start().await;
+        server
+            .mock(Method::GET, "/counts/posts.json")
+            .respond(MockResponse::json(fixtures::COUNTS))
+            .mount();
+        let client = server.client();
+
+        let mut builder = danbooru::SearchTagsBuilder::new();
+        builder.add_tag("cat_ears");
+        assert_eq!(client.count(&builder).await.unwrap(), Some(1234));
+
+        let requests = server.requests();
+        assert_eq!(requests[0].param("tags"), Some(builder.build().as_str()));
+    }
+
+    #[test]
+    fn test_moebooru_base_url() {
+        let client = Client::anonymous(moebooru::Moebooru::Yandere).unwrap();
+        let url = client
+            .compose(yandere::Endpoint::Posts, yandere::Query::posts("landscape"))
+            .unwrap();
+        assert_eq!(url.as_str(), "https://yande.re/post.json?tags=landscape");
+
+        let client = Client::anonymous(moebooru::Moebooru::Konachan).unwrap();
+        assert_eq!(client.base_url().as_str(), "https://konachan.com/");
+    }
+
+    #[tokio::test]
+    async fn test_moebooru_posts() {
+        let server = MockServer::start().await;
+        server
+            .mock(Method::GET, "/post.json")
+            .respond(MockResponse::json(fixtures::MOEBOORU_POSTS))
+            .mount();
+        let client = server
+            .client_builder(moebooru::Moebooru::Yandere)
+            .build()
+            .unwrap();
+
+        let mut builder = moebooru::SearchTagsBuilder::new();
+        builder.add_tag("landscape");
+        builder.rating(moebooru::Rating::Safe);
+        let mut query = moebooru::Query::posts(&builder.build());
+        query.limit(moebooru::MAX_LIMIT);
+        query.page(Page::Before(1100004));
+        let url = client.compose(moebooru::Endpoint::Posts, query).unwrap();
+        let posts = client
+            .fetch::<moebooru::response::Posts>(url, Method::GET)
+            .await
+            .unwrap();
+        assert_eq!(posts.len(), 2);
+
+        let requests = server.requests();
+        assert_eq!(
+            requests[0].param("tags"),
+            Some("landscape rating:s id:<1100004 order:id_desc")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_gelbooru_posts() {
+        let server = MockServer::start().await;
+        server
+            .mock(Method::GET, "/index.php")
+            .query("s", "post")
+            .respond(MockResponse::json(fixtures::GELBOORU_POSTS))
+            .mount();
+        // the default http version also talks to http/1.1 only servers
+        let client = Client::builder(gelbooru::Gelbooru::new(&server.url()))
+            .auth(Auth::new("123456", "secret"))
+            .build()
+            .unwrap();
+
+        let mut query = gelbooru::Query::posts("cat_ears");
+        query.page(2);
+        let url = client.compose(gelbooru::Endpoint::Posts, query).unwrap();
+        let posts = client
+            .fetch::<gelbooru::response::Posts>(url, Method::GET)
+            .await
+            .unwrap();
+        assert_eq!(posts.len(), 2);
+
+        let requests = server.requests();
+        assert_eq!(requests[0].param("page"), Some("dapi"));
+        assert_eq!(requests[0].param("pid"), Some("1"));
+        assert_eq!(requests[0].param("tags"), Some("cat_ears"));
+        assert_eq!(requests[0].param("user_id"), Some("123456"));
+        assert_eq!(requests[0].param("api_key"), Some("secret"));
+        assert_eq!(requests[0].header("authorization"), None);
+    }
+
+    #[test]
+    fn test_default_rate_limiter() {
+        let client = Client::anonymous(Danbooru::Danbooru).unwrap();
+        assert_eq!(
+            client.rate_limiter().quota(RequestKind::Read),
+            Some(Quota::per_second(10.0))
+        );
+
+        // e621 allows far fewer requests than danbooru
+        let client = Client::anonymous(e621::E621::E926).unwrap();
+        assert_eq!(
+            client.rate_limiter().quota(RequestKind::Read),
+            Some(Quota::per_second(2.0))
+        );
+        assert_eq!(
+            client.rate_limiter().quota(RequestKind::Write),
+            Some(Quota::per_second(1.0))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_e621_posts() {
+        let server = MockServer::start().await;
+        server
+            .mock(Method::GET, "/posts.json")
+            .respond(MockResponse::json(fixtures::E621_POSTS))
+            .mount();
+        let client = server
+            .client_builder(e621::E621::E621)
+            .auth(Auth::new("example", "secret"))
+            .build()
+            .unwrap();
+
+        let mut query = e621::Query::posts("wolf");
+        query.page(Page::Before(4800004));
+        let url = client.compose(e621::Endpoint::Posts, query).unwrap();
+        let posts = client
+            .fetch::<e621::response::Posts>(url, Method::GET)
+            .await
+            .unwrap();
+        assert_eq!(posts.len(), 2);
+
+        let requests = server.requests();
+        assert_eq!(requests[0].param("page"), Some("b4800004"));
+        assert!(requests[0]
+            .header("user-agent")
+            .unwrap()
+            .ends_with(" (by example on e621)"));
+        assert!(requests[0].header("authorization").is_some());
+    }
+
+    #[tokio::test]
+    async fn test_e621_error() {
+        let server = MockServer::start().await;
+        server
+            .mock(Method::GET, "/posts/1.json")
+            .respond(
+                MockResponse::status(404).with_json(r#"{"success":false,"reason":"not found"}"#),
+            )
+            .mount();
+        let client = server.client_builder(e621::E621::E621).build().unwrap();
+
+        let url = client
+            .compose(e621::Endpoint::Post(1), e621::Query::new())
+            .unwrap();
+        let err = client
+            .fetch::<e621::response::Post>(url, Method::GET)
+            .await
+            .unwrap_err();
+        let Error::NotFound(http) = err else {
+            panic!("unexpected error: {:?}", err);
+        };
+        assert_eq!(http.api.unwrap().message.as_deref(), Some("not found"));
+    }
+
+    /// posts of any board normalized
+    async fn search<B: BoardApi>(client: &Client<B>, tags: &str) -> Vec<model::Post> {
+        let mut builder = B::SearchBuilder::new();
+        builder.add_tag(tags);
+        B::search_posts(client, &builder, Page::Number(2), 1000)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|post| model::Post::from_native(post, &client.board))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_search_posts() {
+        let server = MockServer::start().await;
+        server
+            .mock(Method::GET, "/post.json")
+            .respond(MockResponse::json(fixtures::MOEBOORU_POSTS))
+            .mount();
+        server
+            .mock(Method::GET, "/index.php")
+            .respond(MockResponse::json(fixtures::GELBOORU_POSTS))
+            .mount();
+
+        let konachan = server
+            .client_builder(moebooru::Moebooru::Konachan)
+            .build()
+            .unwrap();
+        let posts = search(&konachan, "cat_ears").await;
+        assert_eq!(posts[0].id, 1100003);
+        assert_eq!(posts[0].board, "konachan");
+
+        let gelbooru = server
+            .client_builder(gelbooru::Gelbooru::default())
+            .build()
+            .unwrap();
+        let posts = search(&gelbooru, "cat_ears").await;
+        assert_eq!(posts[0].board, "gelbooru");
+
+        let requests = server.requests();
+        assert_eq!(requests[0].param("limit"), Some("100"));
+        assert_eq!(requests[0].param("page"), Some("2"));
+        assert_eq!(requests[1].param("pid"), Some("1"));
+    }
+
+    #[tokio::test]
+    async fn test_get_post() {
+        let server = MockServer::start().await;
+        server
+            .mock(Method::GET, "/posts/8000000.json")
+            .respond(MockResponse::json(fixtures::POST))
+            .mount();
+        server
+            .mock(Method::GET, "/posts/8000001.json")
+            .respond(MockResponse::status(404).with_json(fixtures::NOT_FOUND))
+            .mount();
+        let client = server.client();
+
+        let post = Danbooru::get_post(&client, 8000000).await.unwrap();
+        assert_eq!(post.unwrap().id, 8000000);
+        assert!(Danbooru::get_post(&client, 8000001)
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_get_post_of_engines() {
+        let server = MockServer::start().await;
+        let posts: serde_json::Value = serde_json::from_str(fixtures::E621_POSTS).unwrap();
+        let post = serde_json::json!({ "post": posts["posts"][0] }).to_string();
+        server
+            .mock(Method::GET, "/posts/4800002.json")
+            .respond(MockResponse::json(&post))
+            .mount();
+        server
+            .mock(Method::GET, "/index.php")
+            .respond(MockResponse::json(fixtures::GELBOORU_POSTS))
+            .mount();
+        server
+            .mock(Method::GET, "/post.json")
+            .respond(MockResponse::json("[]"))
+            .mount();
+
+        let e621 = server.client_builder(e621::E621::E621).build().unwrap();
+        let post = e621::E621::get_post(&e621, 4800002).await.unwrap();
+        assert_eq!(post.unwrap().id, 4800002);
+
+        let gelbooru = server
+            .client_builder(gelbooru::Gelbooru::default())
+            .build()
+            .unwrap();
+        assert!(gelbooru::Gelbooru::get_post(&gelbooru, 9000000)
+            .await
+            .unwrap()
+            .is_some());
+
+        let yandere = server
+            .client_builder(moebooru::Moebooru::Yandere)
+            .build()
+            .unwrap();
+        assert!(moebooru::Moebooru::get_post(&yandere, 1100000)
+            .await
+            .unwrap()
+            .is_none());
+
+        let requests = server.requests();
+        assert_eq!(requests[1].param("id"), Some("9000000"));
+        assert_eq!(requests[2].param("tags"), Some("id:1100000"));
+    }
+
+    #[tokio::test]
+    async fn test_get_wiki() {
+        let server = MockServer::start().await;
+        server
+            .mock(Method::GET, "/wiki_pages/cat_ears.json")
+            .respond(MockResponse::json(fixtures::WIKI_PAGE))
+            .mount();
+        let client = server.client();
+
+        let wiki = Danbooru::get_wiki(&client, "cat_ears").await.unwrap();
+        assert_eq!(wiki.title, "cat_ears");
     }
 }
@@ -0,0 +1,382 @@
+use super::Client;
+use crate::board::danbooru::{response, search, Endpoint, Query, SearchTagsBuilder, TagCategory};
+use crate::board::{BoardQuery, BoardSearchTagsBuilder, Page};
+use crate::error::Result;
+use crate::tags::TagGraph;
+use reqwest::Method;
+use std::collections::{HashMap, HashSet};
+
+/// max number of tags searched at once
+const TAG_BATCH_SIZE: usize = 100;
+/// max number of posts searched by ids at once
+const POST_ID_BATCH_SIZE: usize = 100;
+/// max number of records per page
+const MAX_LIMIT: i64 = 1000;
+
+/// Text associated with a post
+#[derive(Debug, Clone, PartialEq)]
+pub struct PostTexts {
+    pub comments: response::Comments,
+    pub notes: response::Notes,
+    pub artist_commentary: Option<response::ArtistCommentary>,
+}
+
+/// Danbooru API helpers
+impl Client {
+    /// Fetch the profile of the current user, which also verifies the credentials
+    pub async fn profile(&self) -> Result<response::Profile> {
+        let url = self.compose(Endpoint::Profile, Query::new())?;
+        self.fetch::<response::Profile>(url, Method::GET).await
+    }
+
+    /// Count posts matching the search, `None` when the board gave up counting
+    pub async fn count(&self, builder: &SearchTagsBuilder) -> Result<Option<i64>> {
+        let url = self.compose(Endpoint::Counts, Query::counts(&builder.build()))?;
+        let counts = self.fetch::<response::Counts>(url, Method::GET).await?;
+        Ok(counts.posts())
+    }
+
+    /// Search tags
+    pub async fn tags(&self, query: Query) -> Result<response::Tags> {
+        let url = self.compose(Endpoint::Tags, query)?;
+        self.fetch::<response::Tags>(url, Method::GET).await
+    }
+
+    /// Get the tag with the exact name
+    pub async fn tag(&self, name: &str) -> Result<Option<response::Tag>> {
+        let mut query = Query::new();
+        query.insert("search[name]", name);
+        query.limit(1);
+        let tags = self.tags(query).await?;
+        Ok(tags.into_iter().find(|tag| tag.name == name))
+    }
+
+    /// Fetch active tag relationships whose antecedent is one of the tags
+    async fn tag_relationships(
+        &self,
+        endpoint: Endpoint,
+        tags: &[String],
+    ) -> Result<Vec<response::TagRelationship>> {
+        let mut relationships = vec![];
+        for chunk in tags.chunks(TAG_BATCH_SIZE) {
+            let chunk = chunk.iter().map(String::as_str).collect::<Vec<_>>();
+            let mut page = Page::Number(1);
+            loop {
+                let mut query = Query::tag_relationships(&chunk);
+                query.limit(MAX_LIMIT);
+                query.page(page);
+                let url = self.compose(endpoint.clone(), query)?;
+                let records = self
+                    .fetch::<Vec<response::TagRelationship>>(url, Method::GET)
+                    .await?;
+                // a short page is the last one
+                let is_full = records.len() as i64 >= MAX_LIMIT;
+                let min_id = records.iter().map(|r| r.id).min();
+                relationships.extend(records);
+                match min_id {
+                    Some(min_id) if is_full => page = Page::Before(min_id),
+                    _ => break,
+                }
+            }
+        }
+        Ok(relationships)
+    }
+
+    /// Build the tag graph of the tags and all tags they imply
+    pub async fn tag_graph(&self, tags: &[&str]) -> Result<TagGraph> {
+        let mut graph = TagGraph::new();
+
+        let tags = tags.iter().map(|t| t.to_string()).collect::<Vec<_>>();
+        for alias in self.tag_relationships(Endpoint::TagAliases, &tags).await? {
+            graph.add_alias(&alias.antecedent_name, &alias.consequent_name);
+        }
+
+        let mut visited = HashSet::new();
+        let mut frontier = graph.canonicalize(tags);
+        while !frontier.is_empty() {
+            visited.extend(frontier.iter().cloned());
+            let mut next = vec![];
+            for implication in self
+                .tag_relationships(Endpoint::TagImplications, &frontier)
+                .await?
+            {
+                graph.add_implication(&implication.antecedent_name, &implication.consequent_name);
+                if !visited.contains(&implication.consequent_name) {
+                    next.push(implication.consequent_name);
+                }
+            }
+            frontier = graph.canonicalize(next);
+        }
+
+        Ok(graph)
+    }
+
+    /// Fetch all active tag relationships of the endpoint
+    async fn all_tag_relationships(
+        &self,
+        endpoint: Endpoint,
+    ) -> Result<Vec<response::TagRelationship>> {
+        let mut relationships = vec![];
+        let mut page = Page::Number(1);
+        loop {
+            let mut query = Query::new();
+            query.insert("search[status]", "active");
+            query.limit(MAX_LIMIT);
+            query.page(page);
+            let url = self.compose(endpoint.clone(), query)?;
+            let records = self
+                .fetch::<Vec<response::TagRelationship>>(url, Method::GET)
+                .await?;
+            // a short page is the last one
+            let is_full = records.len() as i64 >= MAX_LIMIT;
+            let min_id = records.iter().map(|r| r.id).min();
+            relationships.extend(records);
+            match min_id {
+                Some(min_id) if is_full => page = Page::Before(min_id),
+                _ => break,
+            }
+        }
+        Ok(relationships)
+    }
+
+    /// Build the tag graph of all active aliases and implications on the board
+    pub async fn full_tag_graph(&self) -> Result<TagGraph> {
+        let mut graph = TagGraph::new();
+        for alias in self.all_tag_relationships(Endpoint::TagAliases).await? {
+            graph.add_alias(&alias.antecedent_name, &alias.consequent_name);
+        }
+        for implication in self
+            .all_tag_relationships(Endpoint::TagImplications)
+            .await?
+        {
+            graph.add_implication(&implication.antecedent_name, &implication.consequent_name);
+        }
+        Ok(graph)
+    }
+
+    /// Get tags frequently used with the query
+    pub async fn related_tags(
+        &self,
+        query: &str,
+        category: Option<TagCategory>,
+    ) -> Result<response::RelatedTags> {
+        let mut query = Query::related_tag(query);
+        if let Some(category) = category {
+            query.related_category(category);
+        }
+        let url = self.compose(Endpoint::RelatedTag, query)?;
+        self.fetch::<response::RelatedTags>(url, Method::GET).await
+    }
+
+    /// Get tag completions of the query
+    pub async fn autocomplete(&self, query: &str, limit: i64) -> Result<response::Autocomplete> {
+        let mut query = Query::autocomplete(query);
+        query.limit(limit);
+        let url = self.compose(Endpoint::Autocomplete, query)?;
+        self.fetch::<response::Autocomplete>(url, Method::GET).await
+    }
+
+    /// Return the tags of the builder which are not known to the board.
+    /// Negated (`-tag`) and or (`~tag`) tags are checked without the prefix and case-insensitively, and wildcards and metatags are skipped.
+    pub async fn unknown_tags(&self, builder: &SearchTagsBuilder) -> Result<Vec<String>> {
+        let terms = builder
+            .tags()
+            .iter()
+            .flat_map(|t| t.split_whitespace())
+            .filter(|term| !search::is_metatag(term))
+            .map(|term| {
+                (
+                    term.to_string(),
+                    // tag names on the board are lowercase
+                    term.trim_start_matches(['-', '~']).to_lowercase(),
+                )
+            })
+            .filter(|(_, name)| !name.is_empty() && !name.contains('*'))
+            .collect::<Vec<_>>();
+
+        let mut known = HashSet::new();
+        let mut seen = HashSet::new();
+        let names = terms
+            .iter()
+            .map(|(_, name)| name.as_str())
+            .filter(|name| seen.insert(*name))
+            .collect::<Vec<_>>();
+        for chunk in names.chunks(TAG_BATCH_SIZE) {
+            let mut query = Query::tags_by_names(chunk);
+            query.limit(chunk.len() as i64);
+            known.extend(self.tags(query).await?.into_iter().map(|tag| tag.name));
+        }
+
+        Ok(terms
+            .into_iter()
+            .filter(|(_, name)| !known.contains(name))
+            .map(|(term, _)| term)
+            .collect())
+    }
+
+    /// Get the artist
+    pub async fn artist(&self, id: i64) -> Result<response::Artist> {
+        let url = self.compose(Endpoint::Artist(id), Query::artist())?;
+        self.fetch::<response::Artist>(url, Method::GET).await
+    }
+
+    /// Search artists
+    pub async fn artists(&self, query: Query) -> Result<response::Artists> {
+        let url = self.compose(Endpoint::Artists, query)?;
+        self.fetch::<response::Artists>(url, Method::GET).await
+    }
+
+    /// Find the artists who own the url (e.g. the source of a post)
+    pub async fn artists_by_url(&self, source: &str) -> Result<response::Artists> {
+        let url = self.compose(Endpoint::ArtistUrls, Query::artist_urls(source))?;
+        let urls = self.fetch::<response::ArtistUrls>(url, Method::GET).await?;
+
+        let mut artists: response::Artists = vec![];
+        for artist in urls.into_iter().filter_map(|u| u.artist) {
+            if !artists.iter().any(|a| a.id == artist.id) {
+                artists.push(*artist);
+            }
+        }
+        Ok(artists)
+    }
+
+    /// Fetch posts by ids in the same order, skipping posts which are not visible (e.g. expunged)
+    pub async fn posts_by_ids(&self, ids: &[i64]) -> Result<response::Posts> {
+        let mut found = HashMap::new();
+        for chunk in ids.chunks(POST_ID_BATCH_SIZE) {
+            let ids = chunk
+                .iter()
+                .map(|id| id.to_string())
+                .collect::<Vec<_>>()
+                .join(",");
+            let mut query = Query::posts(&format!("id:{}", ids));
+            query.limit(chunk.len() as i64);
+            let url = self.compose(Endpoint::Posts, query)?;
+            for post in self.fetch::<response::Posts>(url, Method::GET).await? {
+                found.insert(post.id, post);
+            }
+        }
+        Ok(ids.iter().filter_map(|id| found.remove(id)).collect())
+    }
+
+    /// Get the pool
+    pub async fn pool(&self, id: i64) -> Result<response::Pool> {
+        let url = self.compose(Endpoint::Pool(id), Query::new())?;
+        self.fetch::<response::Pool>(url, Method::GET).await
+    }
+
+    /// Search pools
+    pub async fn pools(&self, query: Query) -> Result<response::Pools> {
+        let url = self.compose(Endpoint::Pools, query)?;
+        self.fetch::<response::Pools>(url, Method::GET).await
+    }
+
+    /// Fetch all posts of the pool in the pool order
+    pub async fn pool_posts(&self, id: i64) -> Result<response::Posts> {
+        let pool = self.pool(id).await?;
+        self.posts_by_ids(&pool.post_ids).await
+    }
+
+    /// Get the favorite group
+    pub async fn favorite_group(&self, id: i64) -> Result<response::FavoriteGroup> {
+        let url = self.compose(Endpoint::FavoriteGroup(id), Query::new())?;
+        self.fetch::<response::FavoriteGroup>(url, Method::GET)
+            .await
+    }
+
+    /// Search favorite groups
+    pub async fn favorite_groups(&self, query: Query) -> Result<response::FavoriteGroups> {
+        let url = self.compose(Endpoint::FavoriteGroups, query)?;
+        self.fetch::<response::FavoriteGroups>(url, Method::GET)
+            .await
+    }
+
+    /// Fetch all posts of the favorite group in its order
+    pub async fn favorite_group_posts(&self, id: i64) -> Result<response::Posts> {
+        let group = self.favorite_group(id).await?;
+        self.posts_by_ids(&group.post_ids).await
+    }
+
+    /// Fetch comments of the post
+    pub async fn comments(&self, post_id: i64) -> Result<response::Comments> {
+        let url = self.compose(Endpoint::Comments, Query::comments(post_id))?;
+        self.fetch::<response::Comments>(url, Method::GET).await
+    }
+
+    /// Fetch notes of the post
+    pub async fn notes(&self, post_id: i64) -> Result<response::Notes> {
+        let url = self.compose(Endpoint::Notes, Query::notes(post_id))?;
+        self.fetch::<response::Notes>(url, Method::GET).await
+    }
+
+    /// Fetch the artist commentary of the post, if any
+    pub async fn artist_commentary(
+        &self,
+        post_id: i64,
+    ) -> Result<Option<response::ArtistCommentary>> {
+        let url = self.compose(
+            Endpoint::ArtistCommentaries,
+            Query::artist_commentaries(post_id),
+        )?;
+        let commentaries = self
+            .fetch::<response::ArtistCommentaries>(url, Method::GET)
+            .await?;
+        Ok(commentaries.into_iter().next())
+    }
+
+    /// Fetch comments, notes and the artist commentary of the post
+    pub async fn post_texts(&self, post_id: i64) -> Result<PostTexts> {
+        let (comments, notes, artist_commentary) = tokio::try_join!(
+            self.comments(post_id),
+            self.notes(post_id),
+            self.artist_commentary(post_id),
+        )?;
+        Ok(PostTexts {
+            comments,
+            notes,
+            artist_commentary,
+        })
+    }
+
+    /// Search post versions
+    pub async fn post_versions(&self, query: Query) -> Result<response::PostVersions> {
+        let url = self.compose(Endpoint::PostVersions, query)?;
+        self.fetch::<response::PostVersions>(url, Method::GET).await
+    }
+
+    /// Fetch the edit history of the post, oldest first
+    pub async fn post_history(&self, post_id: i64) -> Result<response::PostVersions> {
+        let mut query = Query::post_versions(post_id);
+        query.limit(MAX_LIMIT);
+        let mut versions = self.post_versions(query).await?;
+        versions.sort_by_key(|v| v.version);
+        Ok(versions)
+    }
+
+    /// Fetch a page of post versions newer than the version id, oldest first.
+    /// Pass the id of the last version to the next call to follow all edits on the board.
+    pub async fn post_versions_after(&self, id: i64) -> Result<response::PostVersions> {
+        let mut query = Query::new();
+        query.limit(MAX_LIMIT);
+        query.page(Page::After(id));
+        let mut versions = self.post_versions(query).await?;
+        versions.sort_by_key(|v| v.id);
+        Ok(versions)
+    }
+
+    /// Search wiki page versions
+    pub async fn wiki_page_versions(&self, query: Query) -> Result<response::WikiPageVersions> {
+        let url = self.compose(Endpoint::WikiPageVersions, query)?;
+        self.fetch::<response::WikiPageVersions>(url, Method::GET)
+            .await
+    }
+
+    /// Fetch the edit history of the wiki page, oldest first
+    pub async fn wiki_page_history(&self, wiki_page_id: i64) -> Result<response::WikiPageVersions> {
+        let mut query = Query::wiki_page_versions(wiki_page_id);
+        query.limit(MAX_LIMIT);
+        let mut versions = self.wiki_page_versions(query).await?;
+        versions.sort_by_key(|v| v.id);
+        Ok(versions)
+    }
+}
@@ -0,0 +1,200 @@
+use super::{Auth, AuthMethod, Cassette, Client, RateLimiter, RetryPolicy};
+use crate::board::danbooru::Danbooru;
+use crate::board::BoardApi;
+use crate::error::Result;
+use reqwest::{
+    header::{self, HeaderMap, HeaderName, HeaderValue},
+    Proxy, Url,
+};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// default User-Agent header value
+pub const DEFAULT_USER_AGENT: &str = "danboorust client";
+
+/// HTTP version used to talk to the board
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HttpVersion {
+    /// HTTP/1.1 only
+    Http1,
+    /// HTTP/2 with prior knowledge
+    Http2,
+    /// negotiate HTTP/1.1 or HTTP/2 via ALPN
+    #[default]
+    Negotiate,
+    /// HTTP/3 with prior knowledge
+    #[cfg(feature = "http3")]
+    Http3,
+}
+
+/// Client builder, building clients of the board `B`
+#[derive(Debug)]
+pub struct ClientBuilder<B = Danbooru> {
+    board: B,
+    base_url: Option<String>,
+    auth: Option<Auth>,
+    user_agent: Option<String>,
+    http_version: HttpVersion,
+    timeout: Option<Duration>,
+    connect_timeout: Option<Duration>,
+    proxy: Option<Proxy>,
+    headers: HeaderMap,
+    retry: RetryPolicy,
+    rate_limiter: Option<RateLimiter>,
+    cassette: Option<Cassette>,
+    dry_run: bool,
+}
+
+impl<B: BoardApi> ClientBuilder<B> {
+    /// Create a new ClientBuilder
+    pub fn new(board: B) -> Self {
+        ClientBuilder {
+            board,
+            base_url: None,
+            auth: None,
+            user_agent: None,
+            http_version: HttpVersion::default(),
+            timeout: None,
+            connect_timeout: None,
+            proxy: None,
+            headers: HeaderMap::new(),
+            retry: RetryPolicy::default(),
+            rate_limiter: None,
+            cassette: None,
+            dry_run: false,
+        }
+    }
+
+    /// use another host than the board's default (e.g. self-hosted instances or mock servers)
+    pub fn base_url(mut self, base_url: &str) -> Self {
+        self.base_url = Some(base_url.to_string());
+        self
+    }
+
+    /// set credentials, or `None` for anonymous access
+    pub fn auth<A: Into<Option<Auth>>>(mut self, auth: A) -> Self {
+        self.auth = auth.into();
+        self
+    }
+
+    /// set User-Agent header, e621 requires it to name the project and the user
+    pub fn user_agent(mut self, user_agent: &str) -> Self {
+        self.user_agent = Some(user_agent.to_string());
+        self
+    }
+
+    /// set HTTP version
+    pub fn http_version(mut self, http_version: HttpVersion) -> Self {
+        self.http_version = http_version;
+        self
+    }
+
+    /// set total timeout of each request
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// set connect timeout
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// send requests through the proxy
+    pub fn proxy(mut self, proxy: Proxy) -> Self {
+        self.proxy = Some(proxy);
+        self
+    }
+
+    /// add a header sent with every request
+    pub fn default_header(mut self, name: HeaderName, value: HeaderValue) -> Self {
+        self.headers.insert(name, value);
+        self
+    }
+
+    /// set retry policy
+    pub fn retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// set rate limiter
+    pub fn rate_limiter(mut self, rate_limiter: RateLimiter) -> Self {
+        self.rate_limiter = Some(rate_limiter);
+        self
+    }
+
+    /// record responses to or replay them from the cassette
+    pub fn cassette(mut self, cassette: Cassette) -> Self {
+        self.cassette = Some(cassette);
+        self
+    }
+
+    /// only describe write requests instead of sending them
+    pub fn dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// Build the client
+    pub fn build(self) -> Result<Client<B>> {
+        // boards without basic auth accept credentials only as query parameters
+        let auth = match self.auth {
+            Some(auth) if !B::BASIC_AUTH => Some(Auth {
+                method: AuthMethod::Query,
+                ..auth
+            }),
+            auth => auth,
+        };
+
+        let user_agent = self
+            .user_agent
+            .unwrap_or_else(|| self.board.user_agent(auth.as_ref().map(Auth::username)));
+
+        // create default headers
+        let mut headers = self.headers;
+        headers.insert(header::USER_AGENT, HeaderValue::from_str(&user_agent)?);
+
+        // get client builder and gen client
+        let mut client_builder = reqwest::Client::builder().default_headers(headers);
+        client_builder = match self.http_version {
+            HttpVersion::Http1 => client_builder.http1_only(),
+            HttpVersion::Http2 => client_builder.http2_prior_knowledge(),
+            HttpVersion::Negotiate => client_builder,
+            #[cfg(feature = "http3")]
+            HttpVersion::Http3 => client_builder.http3_prior_knowledge(),
+        };
+        if let Some(timeout) = self.timeout {
+            client_builder = client_builder.timeout(timeout);
+        }
+        if let Some(timeout) = self.connect_timeout {
+            client_builder = client_builder.connect_timeout(timeout);
+        }
+        if let Some(proxy) = self.proxy {
+            client_builder = client_builder.proxy(proxy);
+        }
+        let client = client_builder.build()?;
+
+        // a trailing slash keeps the path prefix when joining endpoint paths
+        let mut base_url = Url::parse(self.base_url.as_deref().unwrap_or(self.board.host()))?;
+        if !base_url.path().ends_with('/') {
+            base_url.set_path(&format!("{}/", base_url.path()));
+        }
+
+        Ok(Client {
+            client: Arc::new(client),
+            retry: self.retry,
+            rate_limiter: Arc::new(
+                self.rate_limiter
+                    .unwrap_or_else(|| self.board.default_rate_limiter()),
+            ),
+            http_version: self.http_version,
+            base_url,
+            auth,
+            cassette: self.cassette.map(Arc::new),
+            dry_run: self.dry_run,
+            board: self.board,
+        })
+    }
+}
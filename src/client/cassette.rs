@@ -0,0 +1,537 @@
+use crate::error::{Error, Result};
+use base64::{engine::general_purpose, Engine};
+use reqwest::{Method, Response, ResponseBuilderExt, Url};
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::{Entry, HashMap};
+use std::path::{Path, PathBuf};
+use tokio::sync::Mutex;
+
+/// query parameters never written to cassettes
+const SECRET_PARAMS: [&str; 4] = ["login", "user_id", "api_key", "password_hash"];
+/// response headers never written to cassettes
+const SECRET_HEADERS: [&str; 3] = ["set-cookie", "authorization", "proxy-authorization"];
+
+/// Whether the cassette talks to the board or not
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CassetteMode {
+    /// send requests and save every response
+    Record,
+    /// return saved responses without sending any request
+    Replay,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum BodyEncoding {
+    Utf8,
+    Base64,
+}
+
+/// Recorded request and response pair
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Interaction {
+    method: String,
+    url: String,
+    status: u16,
+    headers: Vec<(String, String)>,
+    encoding: BodyEncoding,
+    body: String,
+}
+
+impl Interaction {
+    fn new(
+        method: &Method,
+        url: &Url,
+        status: u16,
+        headers: Vec<(String, String)>,
+        body: &[u8],
+    ) -> Self {
+        let (encoding, body) = match std::str::from_utf8(body) {
+            Ok(text) => (BodyEncoding::Utf8, text.to_string()),
+            Err(_) => (BodyEncoding::Base64, general_purpose::STANDARD.encode(body)),
+        };
+        Interaction {
+            method: method.to_string(),
+            url: url.to_string(),
+            status,
+            headers,
+            encoding,
+            body,
+        }
+    }
+
+    fn body(&self) -> Vec<u8> {
+        match self.encoding {
+            BodyEncoding::Utf8 => self.body.as_bytes().to_vec(),
+            BodyEncoding::Base64 => general_purpose::STANDARD
+                .decode(&self.body)
+                .unwrap_or_default(),
+        }
+    }
+
+    fn to_response(&self, url: Url) -> Result<Response> {
+        let mut builder = http::Response::builder().status(self.status).url(url);
+        for (name, value) in &self.headers {
+            builder = builder.header(name, value);
+        }
+        let res = builder
+            .body(self.body())
+            .map_err(|e| Error::Cassette(e.to_string()))?;
+        Ok(Response::from(res))
+    }
+}
+
+/// Responses of the same request, in the order they were recorded
+#[derive(Debug, Default)]
+struct Track {
+    interactions: Vec<Interaction>,
+    cursor: usize,
+}
+
+/// Directory of recorded responses, one JSON file per request
+#[derive(Debug)]
+pub struct Cassette {
+    dir: PathBuf,
+    mode: CassetteMode,
+    tracks: Mutex<HashMap<u64, Track>>,
+}
+
+impl Cassette {
+    /// Record responses into the directory, replacing previous recordings of the same requests
+    pub fn record<P: AsRef<Path>>(dir: P) -> Self {
+        Cassette::new(dir, CassetteMode::Record)
+    }
+
+    /// Replay responses recorded in the directory
+    pub fn replay<P: AsRef<Path>>(dir: P) -> Self {
+        Cassette::new(dir, CassetteMode::Replay)
+    }
+
+    pub fn new<P: AsRef<Path>>(dir: P, mode: CassetteMode) -> Self {
+        Cassette {
+            dir: dir.as_ref().to_path_buf(),
+            mode,
+            tracks: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn mode(&self) -> CassetteMode {
+        self.mode
+    }
+
+    fn path(&self, key: u64) -> PathBuf {
+        self.dir.join(format!("{:016x}.json", key))
+    }
+
+    /// Save the response to the request and return an equivalent one
+    pub(crate) async fn record_response(
+        &self,
+        method: &Method,
+        url: &Url,
+        request_body: &[u8],
+        res: Response,
+    ) -> Result<Response> {
+        // keep the final url after redirects, but save it under the requested one
+        let res_url = res.url().clone();
+        let status = res.status().as_u16();
+        let headers = res
+            .headers()
+            .iter()
+            .filter(|(k, _)| !SECRET_HEADERS.contains(&k.as_str()))
+            .filter_map(|(k, v)| Some((k.to_string(), v.to_str().ok()?.to_string())))
+            .collect();
+        let body = res.bytes().await?;
+        let interaction = Interaction::new(method, &strip_secrets(url), status, headers, &body);
+        let res = interaction.to_response(res_url)?;
+
+        let key = cassette_key(method, url, request_body);
+        let mut tracks = self.tracks.lock().await;
+        let track = tracks.entry(key).or_default();
+        track.interactions.push(interaction);
+
+        tokio::fs::create_dir_all(&self.dir).await?;
+        let json = serde_json::to_string_pretty(&track.interactions)
+            .expect("failed to serialize cassette");
+        tokio::fs::write(self.path(key), json).await?;
+
+        Ok(res)
+    }
+
+    /// Get the next recorded response of the request
+    pub(crate) async fn replay_response(
+        &self,
+        method: &Method,
+        url: &Url,
+        request_body: &[u8],
+    ) -> Result<Response> {
+        let key = cassette_key(method, url, request_body);
+        let mut tracks = self.tracks.lock().await;
+        let track = match tracks.entry(key) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                let json = match tokio::fs::read_to_string(self.path(key)).await {
+                    Ok(json) => json,
+                    Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+                        return Err(Error::CassetteMiss {
+                            method: method.clone(),
+                            url: strip_secrets(url),
+                        });
+                    }
+                    Err(err) => return Err(err.into()),
+                };
+                let interactions =
+                    serde_json::from_str(&json).map_err(|e| Error::decode(e, &json))?;
+                entry.insert(Track {
+                    interactions,
+                    cursor: 0,
+                })
+            }
+        };
+
+        // the last response is repeated once the recorded ones run out
+        let Some(interaction) = track
+            .interactions
+            .get(track.cursor)
+            .or(track.interactions.last())
+        else {
+            return Err(Error::CassetteMiss {
+                method: method.clone(),
+                url: strip_secrets(url),
+            });
+        };
+        let res = interaction.to_response(url.clone())?;
+        track.cursor += 1;
+
+        Ok(res)
+    }
+}
+
+/// remove credentials from the url
+fn strip_secrets(url: &Url) -> Url {
+    let mut url = url.clone();
+    let pairs = url
+        .query_pairs()
+        .filter(|(k, _)| !SECRET_PARAMS.contains(&k.as_ref()))
+        .map(|(k, v)| (k.into_owned(), v.into_owned()))
+        .collect::<Vec<_>>();
+    if pairs.is_empty() {
+        url.set_query(None);
+    } else {
+        url.query_pairs_mut().clear().extend_pairs(pairs);
+    }
+    url
+}
+
+/// stable 64-bit FNV-1a hash of the request without credentials
+fn cassette_key(method: &Method, url: &Url, body: &[u8]) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+    const PRIME: u64 = 0x100000001b3;
+
+    let url = strip_secrets(url);
+    let mut request = format!("{} {}", method, url).into_bytes();
+    // requests without a body keep the keys of older cassettes
+    if !body.is_empty() {
+        request.push(b'\n');
+        request.extend_from_slice(body);
+    }
+    request.iter().fold(OFFSET_BASIS, |hash, b| {
+        (hash ^ *b as u64).wrapping_mul(PRIME)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::danbooru::Danbooru;
+    use crate::board::danbooru::{response, Endpoint, Query};
+    use crate::client::{Auth, Client, FormFile};
+    use crate::mock::{fixtures, MockResponse, MockServer};
+
+    fn temp_dir() -> PathBuf {
+        std::env::temp_dir().join(format!("booru-cassette-{:016x}", rand::random::<u64>()))
+    }
+
+    #[test]
+    fn test_cassette_key() {
+        let url = Url::parse("https://danbooru.donmai.us/posts.json?tags=1girl").unwrap();
+        let with_auth = Url::parse(
+            "https://danbooru.donmai.us/posts.json?tags=1girl&login=username&api_key=PassW0rd%21",
+        )
+        .unwrap();
+
+        assert_eq!(strip_secrets(&with_auth), url);
+        assert_eq!(
+            cassette_key(&Method::GET, &url, &[]),
+            cassette_key(&Method::GET, &with_auth, &[])
+        );
+        assert_ne!(
+            cassette_key(&Method::GET, &url, &[]),
+            cassette_key(&Method::POST, &url, &[])
+        );
+        assert_ne!(
+            cassette_key(&Method::POST, &url, b"score=1"),
+            cassette_key(&Method::POST, &url, b"score=-1")
+        );
+        // must not change between versions, or recorded cassettes become unusable
+        assert_eq!(cassette_key(&Method::GET, &url, &[]), 0xec83fb8a2e278bb8);
+    }
+
+    #[test]
+    fn test_binary_body() {
+        let url = Url::parse("https://cdn.donmai.us/original/00/00/0000.png").unwrap();
+        let body = [0x89, 0x50, 0x4e, 0x47, 0xff, 0x00];
+        let interaction = Interaction::new(&Method::GET, &url, 200, vec![], &body);
+
+        assert_eq!(interaction.encoding, BodyEncoding::Base64);
+        assert_eq!(interaction.body(), body);
+    }
+
+    #[tokio::test]
+    async fn test_record_and_replay() {
+        let dir = temp_dir();
+        let server = MockServer::start().await;
+        server
+            .mock(Method::GET, "/posts/8000000.json")
+            .respond(MockResponse::json(fixtures::POST))
+            .mount();
+        server
+            .mock(Method::GET, "/posts.json")
+            .respond(MockResponse::json(fixtures::POSTS))
+            .respond(MockResponse::json("[]"))
+            .mount();
+
+        let client = server
+            .client_builder(Danbooru::Danbooru)
+            .auth(Auth::query("username", "PassW0rd!"))
+            .cassette(Cassette::record(&dir))
+            .build()
+            .unwrap();
+        let post_url = client
+            .compose(Endpoint::Post(8000000), Query::post())
+            .unwrap();
+        let posts_url = client.compose(Endpoint::Posts, Query::new()).unwrap();
+
+        let post = client
+            .fetch::<response::Post>(post_url.clone(), Method::GET)
+            .await
+            .unwrap();
+        let first = client
+            .fetch::<response::Posts>(posts_url.clone(), Method::GET)
+            .await
+            .unwrap();
+        let second = client
+            .fetch::<response::Posts>(posts_url.clone(), Method::GET)
+            .await
+            .unwrap();
+        assert_eq!(first.len(), 3);
+        assert!(second.is_empty());
+
+        // credentials are not saved
+        let mut entries = std::fs::read_dir(&dir).unwrap().collect::<Vec<_>>();
+        assert_eq!(entries.len(), 2);
+        for entry in entries.drain(..) {
+            let json = std::fs::read_to_string(entry.unwrap().path()).unwrap();
+            assert!(!json.contains("PassW0rd"));
+        }
+
+        // replay without the server
+        drop(server);
+        let client = Client::builder(Danbooru::Danbooru)
+            .base_url(client.base_url().as_str())
+            .cassette(Cassette::replay(&dir))
+            .build()
+            .unwrap();
+
+        let replayed = client
+            .fetch::<response::Post>(post_url, Method::GET)
+            .await
+            .unwrap();
+        assert_eq!(replayed.id, post.id);
+        assert_eq!(replayed.md5, post.md5);
+        let replayed = client
+            .fetch::<response::Posts>(posts_url.clone(), Method::GET)
+            .await
+            .unwrap();
+        assert_eq!(replayed.len(), 3);
+        let replayed = client
+            .fetch::<response::Posts>(posts_url.clone(), Method::GET)
+            .await
+            .unwrap();
+        assert!(replayed.is_empty());
+
+        let url = client.compose(Endpoint::Post(1), Query::post()).unwrap();
+        let err = client
+            .fetch::<response::Post>(url, Method::GET)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::CassetteMiss { .. }));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_record_redirects_and_bodies() {
+        let dir = temp_dir();
+        let server = MockServer::start().await;
+        server
+            .mock(Method::GET, "/posts/random.json")
+            .respond(
+                MockResponse::status(302)
+                    .with_header("location", "/posts/8000000.json")
+                    .with_header("set-cookie", "_danbooru2_session=secret"),
+            )
+            .mount();
+        server
+            .mock(Method::GET, "/posts/8000000.json")
+            .respond(MockResponse::json(fixtures::POST).with_header("set-cookie", "secret=1"))
+            .mount();
+        server
+            .mock(Method::POST, "/posts/8000000/votes.json")
+            .respond(MockResponse::json(r#"{"score":1}"#))
+            .respond(MockResponse::json(r#"{"score":-1}"#))
+            .mount();
+
+        let client = server
+            .client_builder(Danbooru::Danbooru)
+            .cassette(Cassette::record(&dir))
+            .build()
+            .unwrap();
+        let random_url = client.base_url().join("posts/random.json").unwrap();
+        let votes_url = client.base_url().join("posts/8000000/votes.json").unwrap();
+        let vote = |score: &str| vec![("score".to_string(), score.to_string())];
+
+        client
+            .fetch_raw(random_url.clone(), Method::GET)
+            .await
+            .unwrap();
+        for score in ["1", "-1"] {
+            client
+                .submit_raw(votes_url.clone(), Method::POST, &vote(score))
+                .await
+                .unwrap();
+        }
+        for entry in std::fs::read_dir(&dir).unwrap() {
+            let json = std::fs::read_to_string(entry.unwrap().path()).unwrap();
+            assert!(!json.contains("secret"));
+        }
+
+        // replay without the server, in another order
+        drop(server);
+        let client = Client::builder(Danbooru::Danbooru)
+            .base_url(client.base_url().as_str())
+            .cassette(Cassette::replay(&dir))
+            .build()
+            .unwrap();
+
+        let res = client
+            .submit_raw(votes_url.clone(), Method::POST, &vote("-1"))
+            .await
+            .unwrap();
+        assert_eq!(res.text().await.unwrap(), r#"{"score":-1}"#);
+        let res = client
+            .submit_raw(votes_url, Method::POST, &vote("1"))
+            .await
+            .unwrap();
+        assert_eq!(res.text().await.unwrap(), r#"{"score":1}"#);
+        let post = client
+            .fetch::<response::Post>(random_url, Method::GET)
+            .await
+            .unwrap();
+        assert_eq!(post.id, 8000000);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_record_multipart() {
+        let dir = temp_dir();
+        let server = MockServer::start().await;
+        server
+            .mock(Method::POST, "/uploads.json")
+            .respond(MockResponse::json(r#"{"id":1}"#))
+            .respond(MockResponse::json(r#"{"id":2}"#))
+            .mount();
+
+        let client = server
+            .client_builder(Danbooru::Danbooru)
+            .cassette(Cassette::record(&dir))
+            .build()
+            .unwrap();
+        let url = client.base_url().join("uploads.json").unwrap();
+        let file = |name: &str, bytes: &[u8]| FormFile {
+            field: "upload[files][0]".to_string(),
+            file_name: name.to_string(),
+            bytes: bytes.to_vec(),
+        };
+        let files = [
+            file("cat.png", b"cat"),
+            file("dog.png", b"dog"),
+            file("cat.png", b"kitten"),
+        ];
+
+        for file in &files[..2] {
+            client
+                .submit_multipart_raw(url.clone(), Method::POST, &[], file)
+                .await
+                .unwrap();
+        }
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 2);
+
+        // replay without the server, in another order
+        drop(server);
+        let client = Client::builder(Danbooru::Danbooru)
+            .base_url(client.base_url().as_str())
+            .cassette(Cassette::replay(&dir))
+            .build()
+            .unwrap();
+
+        let res = client
+            .submit_multipart_raw(url.clone(), Method::POST, &[], &files[1])
+            .await
+            .unwrap();
+        assert_eq!(res.text().await.unwrap(), r#"{"id":2}"#);
+        let res = client
+            .submit_multipart_raw(url.clone(), Method::POST, &[], &files[0])
+            .await
+            .unwrap();
+        assert_eq!(res.text().await.unwrap(), r#"{"id":1}"#);
+        // same file name with other bytes was never recorded
+        let err = client
+            .submit_multipart_raw(url, Method::POST, &[], &files[2])
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::CassetteMiss { .. }));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_replay_invalid_cassette() {
+        let dir = temp_dir();
+        let url = Url::parse("https://danbooru.donmai.us/posts.json").unwrap();
+        let mut interaction = Interaction::new(&Method::GET, &url, 200, vec![], b"[]");
+        interaction.status = 1000;
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join(format!(
+                "{:016x}.json",
+                cassette_key(&Method::GET, &url, &[])
+            )),
+            serde_json::to_string(&vec![interaction]).unwrap(),
+        )
+        .unwrap();
+
+        let cassette = Cassette::replay(&dir);
+        let err = cassette
+            .replay_response(&Method::GET, &url, &[])
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::Cassette(_)));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,278 @@
+use reqwest::Method;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Kind of request, each of them has its own budget
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RequestKind {
+    /// GET, HEAD and OPTIONS requests
+    Read,
+    /// requests which update something on the board
+    Write,
+}
+
+impl RequestKind {
+    pub fn from_method(method: &Method) -> Self {
+        match *method {
+            Method::GET | Method::HEAD | Method::OPTIONS => RequestKind::Read,
+            _ => RequestKind::Write,
+        }
+    }
+}
+
+/// Token bucket quota
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Quota {
+    /// tokens regenerated per second
+    pub per_second: f64,
+    /// max number of tokens which can be spent at once
+    pub burst: u32,
+}
+
+impl Quota {
+    /// quota with `n` requests per second and the same burst size
+    pub fn per_second(n: f64) -> Self {
+        Quota {
+            per_second: n,
+            burst: n.ceil().max(1.0) as u32,
+        }
+    }
+
+    /// set burst size
+    pub fn burst(mut self, burst: u32) -> Self {
+        self.burst = burst.max(1);
+        self
+    }
+}
+
+/// Waiting statistics of a bucket
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RateLimitStats {
+    /// number of requests passed through the limiter
+    pub requests: u64,
+    /// number of requests which had to wait
+    pub throttled: u64,
+    /// total waiting time
+    pub total_wait: Duration,
+    /// longest waiting time
+    pub max_wait: Duration,
+}
+
+impl RateLimitStats {
+    /// average waiting time per request
+    pub fn average_wait(&self) -> Duration {
+        if self.requests == 0 {
+            return Duration::ZERO;
+        }
+        self.total_wait.div_f64(self.requests as f64)
+    }
+}
+
+#[derive(Debug)]
+struct Bucket {
+    quota: Option<Quota>,
+    tokens: f64,
+    updated_at: Instant,
+    stats: RateLimitStats,
+}
+
+impl Bucket {
+    fn new(quota: Option<Quota>) -> Self {
+        Bucket {
+            quota,
+            tokens: quota.map(|q| q.burst as f64).unwrap_or_default(),
+            updated_at: Instant::now(),
+            stats: RateLimitStats::default(),
+        }
+    }
+
+    /// take one token and return how long the caller has to wait for it
+    fn reserve(&mut self, now: Instant) -> Duration {
+        let wait = match self.quota {
+            Some(quota) if quota.per_second > 0.0 => {
+                let elapsed = now.saturating_duration_since(self.updated_at);
+                self.tokens = (self.tokens + elapsed.as_secs_f64() * quota.per_second)
+                    .min(quota.burst as f64);
+                self.updated_at = now;
+
+                // tokens may become negative, which queues the following callers
+                self.tokens -= 1.0;
+                if self.tokens >= 0.0 {
+                    Duration::ZERO
+                } else {
+                    Duration::from_secs_f64(-self.tokens / quota.per_second)
+                }
+            }
+            _ => Duration::ZERO,
+        };
+
+        self.stats.requests += 1;
+        if !wait.is_zero() {
+            self.stats.throttled += 1;
+            self.stats.total_wait += wait;
+            self.stats.max_wait = self.stats.max_wait.max(wait);
+        }
+
+        wait
+    }
+}
+
+/// Token bucket rate limiter with separate read and write budgets
+#[derive(Debug)]
+pub struct RateLimiter {
+    read: Mutex<Bucket>,
+    write: Mutex<Bucket>,
+}
+
+impl Default for RateLimiter {
+    fn default() -> Self {
+        RateLimiter::danbooru()
+    }
+}
+
+impl RateLimiter {
+    /// Create a new RateLimiter. `None` means unlimited.
+    pub fn new(read: Option<Quota>, write: Option<Quota>) -> Self {
+        RateLimiter {
+            read: Mutex::new(Bucket::new(read)),
+            write: Mutex::new(Bucket::new(write)),
+        }
+    }
+
+    /// Danbooru's default limits (10 reads/s, 1 write/s with a burst of 10)
+    pub fn danbooru() -> Self {
+        RateLimiter::new(
+            Some(Quota::per_second(10.0)),
+            Some(Quota::per_second(1.0).burst(10)),
+        )
+    }
+
+    /// e621's limits (2 requests/s, recommended 1 write/s)
+    pub fn e621() -> Self {
+        RateLimiter::new(Some(Quota::per_second(2.0)), Some(Quota::per_second(1.0)))
+    }
+
+    /// No rate limiting
+    pub fn unlimited() -> Self {
+        RateLimiter::new(None, None)
+    }
+
+    /// Replace the read quota
+    pub fn read_quota(self, quota: Option<Quota>) -> Self {
+        RateLimiter {
+            read: Mutex::new(Bucket::new(quota)),
+            ..self
+        }
+    }
+
+    /// Replace the write quota
+    pub fn write_quota(self, quota: Option<Quota>) -> Self {
+        RateLimiter {
+            write: Mutex::new(Bucket::new(quota)),
+            ..self
+        }
+    }
+
+    fn bucket(&self, kind: RequestKind) -> &Mutex<Bucket> {
+        match kind {
+            RequestKind::Read => &self.read,
+            RequestKind::Write => &self.write,
+        }
+    }
+
+    /// Wait until a request of the kind is allowed, and return how long it waited
+    pub async fn acquire(&self, kind: RequestKind) -> Duration {
+        let wait = self.bucket(kind).lock().unwrap().reserve(Instant::now());
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+        wait
+    }
+
+    /// Get the quota of the kind
+    pub fn quota(&self, kind: RequestKind) -> Option<Quota> {
+        self.bucket(kind).lock().unwrap().quota
+    }
+
+    /// Get waiting statistics of the kind
+    pub fn stats(&self, kind: RequestKind) -> RateLimitStats {
+        self.bucket(kind).lock().unwrap().stats.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_kind() {
+        assert_eq!(RequestKind::from_method(&Method::GET), RequestKind::Read);
+        assert_eq!(RequestKind::from_method(&Method::HEAD), RequestKind::Read);
+        assert_eq!(RequestKind::from_method(&Method::POST), RequestKind::Write);
+        assert_eq!(RequestKind::from_method(&Method::PUT), RequestKind::Write);
+        assert_eq!(
+            RequestKind::from_method(&Method::DELETE),
+            RequestKind::Write
+        );
+    }
+
+    #[test]
+    fn test_bucket_reserve() {
+        let mut bucket = Bucket::new(Some(Quota::per_second(2.0)));
+        let now = bucket.updated_at;
+
+        // burst
+        assert_eq!(bucket.reserve(now), Duration::ZERO);
+        assert_eq!(bucket.reserve(now), Duration::ZERO);
+
+        // queued
+        assert_eq!(bucket.reserve(now), Duration::from_millis(500));
+        assert_eq!(bucket.reserve(now), Duration::from_millis(1000));
+
+        // refilled
+        let later = now + Duration::from_secs(3);
+        assert_eq!(bucket.reserve(later), Duration::ZERO);
+
+        let stats = bucket.stats;
+        assert_eq!(stats.requests, 5);
+        assert_eq!(stats.throttled, 2);
+        assert_eq!(stats.total_wait, Duration::from_millis(1500));
+        assert_eq!(stats.max_wait, Duration::from_millis(1000));
+    }
+
+    #[test]
+    fn test_unlimited_bucket() {
+        let mut bucket = Bucket::new(None);
+        let now = Instant::now();
+
+        for _ in 0..100 {
+            assert_eq!(bucket.reserve(now), Duration::ZERO);
+        }
+        assert_eq!(bucket.stats.requests, 100);
+        assert_eq!(bucket.stats.throttled, 0);
+    }
+
+    #[tokio::test]
+    async fn test_shared_limiter() {
+        let limiter = std::sync::Arc::new(RateLimiter::new(
+            Some(Quota::per_second(20.0).burst(1)),
+            None,
+        ));
+
+        let start = Instant::now();
+        let tasks = (0..3)
+            .map(|_| {
+                let limiter = limiter.clone();
+                tokio::spawn(async move { limiter.acquire(RequestKind::Read).await })
+            })
+            .collect::<Vec<_>>();
+        for task in tasks {
+            task.await.unwrap();
+        }
+
+        // 1 immediate + 2 queued at 50ms intervals
+        assert!(start.elapsed() >= Duration::from_millis(90));
+        assert_eq!(limiter.stats(RequestKind::Read).throttled, 2);
+        assert_eq!(limiter.stats(RequestKind::Write).requests, 0);
+    }
+}
@@ -0,0 +1,247 @@
+use rand::Rng;
+use reqwest::{header, Method, Response, StatusCode};
+use std::error::Error as StdError;
+use std::io::ErrorKind;
+use std::time::{Duration, SystemTime};
+
+/// Retry policy used by the client for transient failures
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// max number of attempts, including the first request
+    pub max_attempts: u32,
+    /// delay before the first retry
+    pub base_delay: Duration,
+    /// upper bound of the backoff delay and the `Retry-After` delay
+    pub max_delay: Duration,
+    /// randomize the backoff delay to avoid thundering herds
+    pub jitter: bool,
+    /// wait as long as the `Retry-After` header says
+    pub respect_retry_after: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_attempts: 5,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(60),
+            jitter: true,
+            respect_retry_after: true,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// never retry
+    pub fn none() -> Self {
+        RetryPolicy {
+            max_attempts: 1,
+            ..Default::default()
+        }
+    }
+
+    /// set max attempts
+    pub fn max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    /// set base delay
+    pub fn base_delay(mut self, base_delay: Duration) -> Self {
+        self.base_delay = base_delay;
+        self
+    }
+
+    /// set max delay
+    pub fn max_delay(mut self, max_delay: Duration) -> Self {
+        self.max_delay = max_delay;
+        self
+    }
+
+    /// enable or disable jitter
+    pub fn jitter(mut self, jitter: bool) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// enable or disable `Retry-After` handling
+    pub fn respect_retry_after(mut self, respect_retry_after: bool) -> Self {
+        self.respect_retry_after = respect_retry_after;
+        self
+    }
+
+    /// exponential backoff delay after the `attempt`-th failed attempt (1-based)
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let exp = attempt.saturating_sub(1).min(31);
+        let delay = self.base_delay.saturating_mul(1 << exp).min(self.max_delay);
+
+        if self.jitter && !delay.is_zero() {
+            // "equal jitter": keep at least half of the delay
+            let half = delay / 2;
+            let extra = rand::thread_rng().gen_range(0..=half.as_millis() as u64);
+            half + Duration::from_millis(extra)
+        } else {
+            delay
+        }
+    }
+
+    /// delay before retrying a response with a retryable status
+    pub fn delay_for_response(&self, attempt: u32, res: &Response) -> Duration {
+        if self.respect_retry_after {
+            if let Some(delay) = res
+                .headers()
+                .get(header::RETRY_AFTER)
+                .and_then(|v| v.to_str().ok())
+                .and_then(parse_retry_after)
+            {
+                // don't let the server make the client sleep for hours
+                return delay.min(self.max_delay);
+            }
+        }
+        self.backoff(attempt)
+    }
+
+    /// whether the response should be retried
+    pub fn should_retry_status(&self, method: &Method, status: StatusCode) -> bool {
+        match status {
+            // the request was rejected before being processed, so it is always safe to retry
+            StatusCode::TOO_MANY_REQUESTS => true,
+            StatusCode::INTERNAL_SERVER_ERROR
+            | StatusCode::BAD_GATEWAY
+            | StatusCode::SERVICE_UNAVAILABLE
+            | StatusCode::GATEWAY_TIMEOUT => is_idempotent(method),
+            _ => false,
+        }
+    }
+
+    /// whether the transport error should be retried
+    pub fn should_retry_error(&self, method: &Method, err: &reqwest::Error) -> bool {
+        if err.is_connect() {
+            // the request has not been sent yet
+            return true;
+        }
+        is_idempotent(method) && (err.is_timeout() || is_connection_reset(err))
+    }
+}
+
+fn is_idempotent(method: &Method) -> bool {
+    !matches!(*method, Method::POST | Method::PATCH)
+}
+
+fn is_connection_reset(err: &reqwest::Error) -> bool {
+    let mut source = err.source();
+    while let Some(err) = source {
+        if let Some(io) = err.downcast_ref::<std::io::Error>() {
+            return matches!(
+                io.kind(),
+                ErrorKind::ConnectionReset
+                    | ErrorKind::ConnectionAborted
+                    | ErrorKind::BrokenPipe
+                    | ErrorKind::UnexpectedEof
+            );
+        }
+        source = err.source();
+    }
+    false
+}
+
+/// parse `Retry-After` header value (delay-seconds or HTTP-date)
+pub fn parse_retry_after(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+    if let Ok(secs) = value.parse::<f64>() {
+        if secs.is_finite() && secs >= 0.0 {
+            return Some(Duration::from_secs_f64(secs));
+        }
+    }
+    let date = httpdate::parse_http_date(value).ok()?;
+    Some(
+        date.duration_since(SystemTime::now())
+            .unwrap_or(Duration::ZERO),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_without_jitter() {
+        let policy = RetryPolicy::default()
+            .base_delay(Duration::from_millis(100))
+            .max_delay(Duration::from_secs(1))
+            .jitter(false);
+
+        assert_eq!(policy.backoff(1), Duration::from_millis(100));
+        assert_eq!(policy.backoff(2), Duration::from_millis(200));
+        assert_eq!(policy.backoff(3), Duration::from_millis(400));
+        assert_eq!(policy.backoff(5), Duration::from_secs(1));
+        assert_eq!(policy.backoff(100), Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_backoff_with_jitter() {
+        let policy = RetryPolicy::default()
+            .base_delay(Duration::from_millis(100))
+            .max_delay(Duration::from_secs(1));
+
+        for attempt in 1..10 {
+            let delay = policy.backoff(attempt);
+            let max = policy.clone().jitter(false).backoff(attempt);
+            assert!(delay >= max / 2);
+            assert!(delay <= max);
+        }
+    }
+
+    #[test]
+    fn test_delay_for_response() {
+        let policy = RetryPolicy::default()
+            .max_delay(Duration::from_secs(10))
+            .jitter(false);
+        let response = |retry_after: &str| {
+            Response::from(
+                http::Response::builder()
+                    .status(StatusCode::TOO_MANY_REQUESTS)
+                    .header(header::RETRY_AFTER, retry_after)
+                    .body("")
+                    .unwrap(),
+            )
+        };
+
+        assert_eq!(
+            policy.delay_for_response(1, &response("3")),
+            Duration::from_secs(3)
+        );
+        assert_eq!(
+            policy.delay_for_response(1, &response("86400")),
+            Duration::from_secs(10)
+        );
+    }
+
+    #[test]
+    fn test_should_retry_status() {
+        let policy = RetryPolicy::default();
+
+        assert!(policy.should_retry_status(&Method::GET, StatusCode::TOO_MANY_REQUESTS));
+        assert!(policy.should_retry_status(&Method::GET, StatusCode::BAD_GATEWAY));
+        assert!(policy.should_retry_status(&Method::PUT, StatusCode::SERVICE_UNAVAILABLE));
+        assert!(policy.should_retry_status(&Method::POST, StatusCode::TOO_MANY_REQUESTS));
+        assert!(!policy.should_retry_status(&Method::POST, StatusCode::BAD_GATEWAY));
+        assert!(!policy.should_retry_status(&Method::GET, StatusCode::NOT_FOUND));
+        assert!(!policy.should_retry_status(&Method::GET, StatusCode::OK));
+    }
+
+    #[test]
+    fn test_parse_retry_after() {
+        assert_eq!(parse_retry_after("3"), Some(Duration::from_secs(3)));
+        assert_eq!(parse_retry_after(" 0.5 "), Some(Duration::from_millis(500)));
+        assert_eq!(
+            parse_retry_after("Wed, 21 Oct 2015 07:28:00 GMT"),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(parse_retry_after("soon"), None);
+        assert_eq!(parse_retry_after("-1"), None);
+    }
+}
@@ -0,0 +1,275 @@
+use super::Client;
+use crate::board::danbooru::{response, Endpoint, Query, SearchTagsBuilder};
+use crate::board::{BoardQuery, BoardSearchTagsBuilder, Page};
+use crate::error::Result;
+use futures::stream::{self, BoxStream, StreamExt};
+use reqwest::Method;
+use tokio::task::JoinHandle;
+
+/// How to walk through the search results
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PageMode {
+    /// numbered pages starting from the page (Danbooru allows up to page 1000)
+    Numbered(i64),
+    /// posts whose id is lower than the id, newest first
+    Before(i64),
+    /// posts whose id is greater than the id, oldest first
+    After(i64),
+}
+
+impl From<PageMode> for Page {
+    fn from(mode: PageMode) -> Self {
+        match mode {
+            PageMode::Numbered(page) => Page::Number(page),
+            PageMode::Before(id) => Page::Before(id),
+            PageMode::After(id) => Page::After(id),
+        }
+    }
+}
+
+impl PageMode {
+    /// the page after the fetched posts
+    fn next(&self, posts: &response::Posts) -> Self {
+        match self {
+            PageMode::Numbered(page) => PageMode::Numbered(page + 1),
+            PageMode::Before(id) => {
+                PageMode::Before(posts.iter().map(|p| p.id).min().unwrap_or(*id))
+            }
+            PageMode::After(id) => PageMode::After(posts.iter().map(|p| p.id).max().unwrap_or(*id)),
+        }
+    }
+}
+
+/// Options of posts stream
+#[derive(Debug, Clone)]
+pub struct PostsStreamOptions {
+    /// posts per request
+    pub limit: i64,
+    /// max number of posts to yield in total
+    pub max_posts: Option<usize>,
+    /// fetch the next page while the current page is consumed
+    pub prefetch: bool,
+}
+
+impl Default for PostsStreamOptions {
+    fn default() -> Self {
+        PostsStreamOptions {
+            limit: 200,
+            max_posts: None,
+            prefetch: true,
+        }
+    }
+}
+
+struct PostsStreamState {
+    client: Client,
+    query: Query,
+    page: PageMode,
+    prefetched: Option<JoinHandle<Result<response::Posts>>>,
+    remaining: Option<usize>,
+    prefetch: bool,
+    done: bool,
+}
+
+impl PostsStreamState {
+    async fn next_page(mut self) -> Option<(Vec<Result<response::Post>>, Self)> {
+        if self.done {
+            return None;
+        }
+
+        let posts = match self.prefetched.take() {
+            Some(handle) => match handle.await {
+                Ok(posts) => posts,
+                Err(err) => std::panic::resume_unwind(err.into_panic()),
+            },
+            None => fetch_page(self.client.clone(), self.query.clone(), self.page).await,
+        };
+        let mut posts = match posts {
+            Ok(posts) => posts,
+            Err(err) => {
+                self.done = true;
+                return Some((vec![Err(err)], self));
+            }
+        };
+        if posts.is_empty() {
+            return None;
+        }
+
+        self.page = self.page.next(&posts);
+        if let PageMode::After(_) = self.page {
+            // danbooru returns newer posts first even if paging forward
+            posts.sort_by_key(|p| p.id);
+        }
+        if let Some(remaining) = self.remaining {
+            posts.truncate(remaining);
+            self.remaining = Some(remaining - posts.len());
+            self.done = remaining == posts.len();
+        }
+
+        if self.prefetch && !self.done {
+            self.prefetched = Some(tokio::spawn(fetch_page(
+                self.client.clone(),
+                self.query.clone(),
+                self.page,
+            )));
+        }
+
+        Some((posts.into_iter().map(Ok).collect(), self))
+    }
+}
+
+impl Drop for PostsStreamState {
+    fn drop(&mut self) {
+        if let Some(handle) = self.prefetched.take() {
+            handle.abort();
+        }
+    }
+}
+
+async fn fetch_page(client: Client, mut query: Query, page: PageMode) -> Result<response::Posts> {
+    query.page(page);
+    let url = client.compose(Endpoint::Posts, query)?;
+    client.fetch::<response::Posts>(url, Method::GET).await
+}
+
+/// Paginated search
+impl Client {
+    /// Stream all posts matching the search tags
+    pub fn posts_stream(
+        &self,
+        builder: SearchTagsBuilder,
+        mode: PageMode,
+    ) -> BoxStream<'static, Result<response::Post>> {
+        self.posts_stream_with(builder, mode, PostsStreamOptions::default())
+    }
+
+    /// Stream all posts matching the search tags with options
+    pub fn posts_stream_with(
+        &self,
+        builder: SearchTagsBuilder,
+        mode: PageMode,
+        options: PostsStreamOptions,
+    ) -> BoxStream<'static, Result<response::Post>> {
+        let mut query = Query::posts(&builder.build());
+        query.limit(options.limit);
+
+        let state = PostsStreamState {
+            client: self.clone(),
+            query,
+            page: mode,
+            prefetched: None,
+            remaining: options.max_posts,
+            prefetch: options.prefetch,
+            done: options.max_posts == Some(0),
+        };
+
+        stream::unfold(state, PostsStreamState::next_page)
+            .flat_map(stream::iter)
+            .boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{fixtures, MockResponse, MockServer};
+    use futures::TryStreamExt;
+
+    fn posts(ids: &[i64]) -> response::Posts {
+        let post: response::Post =
+            serde_json::from_str(include_str!("../../tests/fixtures/danbooru/post.json")).unwrap();
+        ids.iter()
+            .map(|id| response::Post {
+                id: *id,
+                ..post.clone()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_page_mode_to_page() {
+        assert_eq!(Page::from(PageMode::Numbered(3)), Page::Number(3));
+        assert_eq!(Page::from(PageMode::Before(1234)), Page::Before(1234));
+        assert_eq!(Page::from(PageMode::After(1234)), Page::After(1234));
+    }
+
+    #[test]
+    fn test_page_mode_next() {
+        let page = posts(&[30, 20, 10]);
+
+        assert_eq!(PageMode::Numbered(1).next(&page), PageMode::Numbered(2));
+        assert_eq!(PageMode::Before(100).next(&page), PageMode::Before(10));
+        assert_eq!(PageMode::After(0).next(&page), PageMode::After(30));
+    }
+
+    #[tokio::test]
+    async fn test_posts_stream() {
+        let server = MockServer::start().await;
+        server
+            .mock(Method::GET, "/posts.json")
+            .respond(MockResponse::json("[]"))
+            .mount();
+        server
+            .mock(Method::GET, "/posts.json")
+            .query("page", "b8100001")
+            .respond(MockResponse::json(
+                &serde_json::to_string(&posts(&[5, 4])).unwrap(),
+            ))
+            .mount();
+        server
+            .mock(Method::GET, "/posts.json")
+            .query("page", "b9000000")
+            .respond(MockResponse::json(fixtures::POSTS))
+            .mount();
+        let client = server.client();
+
+        let mut builder = SearchTagsBuilder::new();
+        builder.add_tag("cat_ears");
+        let ids = client
+            .posts_stream(builder.clone(), PageMode::Before(9000000))
+            .map_ok(|p| p.id)
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        assert_eq!(ids, vec![8100003, 8100002, 8100001, 5, 4]);
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 3);
+        assert_eq!(requests[0].param("tags"), Some(builder.build().as_str()));
+        assert_eq!(requests[0].param("limit"), Some("200"));
+        assert_eq!(requests[2].param("page"), Some("b4"));
+
+        // stops after max_posts without prefetching
+        let options = PostsStreamOptions {
+            max_posts: Some(2),
+            ..Default::default()
+        };
+        let ids = client
+            .posts_stream_with(builder, PageMode::Before(9000000), options)
+            .map_ok(|p| p.id)
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        assert_eq!(ids, vec![8100003, 8100002]);
+        assert_eq!(server.requests().len(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_posts_stream_error() {
+        let server = MockServer::start().await;
+        server
+            .mock(Method::GET, "/posts.json")
+            .respond(MockResponse::status(422).with_json(
+                r#"{"success":false,"message":"You cannot search for more than 2 tags at a time."}"#,
+            ))
+            .mount();
+        let client = server.client();
+
+        let results = client
+            .posts_stream(SearchTagsBuilder::new(), PageMode::Numbered(1))
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(results.len(), 1);
+        assert!(matches!(results[0], Err(crate::Error::ClientError(_))));
+    }
+}
@@ -0,0 +1,267 @@
+use super::{Client, FormFile, WriteOutcome};
+use crate::board::danbooru::{response, Endpoint, Query};
+use crate::board::BoardQuery;
+use crate::error::{Error, Result};
+use reqwest::Method;
+use std::time::{Duration, Instant};
+
+/// interval of polling uploads
+const UPLOAD_POLL_INTERVAL: Duration = Duration::from_secs(1);
+/// max time to wait for uploads to be processed
+const UPLOAD_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// What to upload
+#[derive(Debug, Clone, PartialEq)]
+pub enum UploadSource {
+    /// local file
+    File { file_name: String, bytes: Vec<u8> },
+    /// url of the image or the page containing it, fetched by the board
+    Url {
+        url: String,
+        referer: Option<String>,
+    },
+}
+
+impl UploadSource {
+    pub fn file(file_name: &str, bytes: Vec<u8>) -> Self {
+        UploadSource::File {
+            file_name: file_name.to_string(),
+            bytes,
+        }
+    }
+
+    pub fn url(url: &str) -> Self {
+        UploadSource::Url {
+            url: url.to_string(),
+            referer: None,
+        }
+    }
+}
+
+/// Danbooru upload API
+impl Client {
+    /// Get the upload with its media assets
+    pub async fn upload(&self, id: i64) -> Result<response::Upload> {
+        let url = self.compose(Endpoint::Upload(id), Query::upload())?;
+        self.fetch::<response::Upload>(url, Method::GET).await
+    }
+
+    /// Start uploading, the board processes the files in the background
+    pub async fn create_upload(
+        &self,
+        source: UploadSource,
+    ) -> Result<WriteOutcome<response::Upload>> {
+        let request = match source {
+            UploadSource::File { file_name, bytes } => {
+                let mut request =
+                    self.write_request(Endpoint::Uploads, Method::POST, Query::new())?;
+                request.file = Some(FormFile {
+                    field: "upload[files][0]".to_string(),
+                    file_name,
+                    bytes,
+                });
+                request
+            }
+            UploadSource::Url { url, referer } => self.write_request(
+                Endpoint::Uploads,
+                Method::POST,
+                Query::upload_source(&url, referer.as_deref()),
+            )?,
+        };
+        self.write(request).await
+    }
+
+    /// Poll the upload until it is processed, failing if it is not done within the timeout
+    pub async fn wait_for_upload(
+        &self,
+        id: i64,
+        interval: Duration,
+        timeout: Duration,
+    ) -> Result<response::Upload> {
+        let started_at = Instant::now();
+        loop {
+            let upload = self.upload(id).await?;
+            match upload.status {
+                response::UploadStatus::Completed => return Ok(upload),
+                response::UploadStatus::Error => {
+                    return Err(Error::Upload {
+                        id,
+                        message: upload.error.unwrap_or_default(),
+                    })
+                }
+                _ if started_at.elapsed() >= timeout => {
+                    return Err(Error::Upload {
+                        id,
+                        message: format!("not processed in {:?}", timeout),
+                    })
+                }
+                _ => tokio::time::sleep(interval).await,
+            }
+        }
+    }
+
+    /// Create a post from the media asset of an upload, set the post with the `Query::post_update` setters
+    pub async fn create_post(
+        &self,
+        upload_media_asset_id: i64,
+        changes: Query,
+    ) -> Result<WriteOutcome<response::Post>> {
+        let mut query = Query::post_create(upload_media_asset_id);
+        for (key, value) in changes.pairs() {
+            query.insert(key, value);
+        }
+        let request = self.write_request(Endpoint::Posts, Method::POST, query)?;
+        self.write(request).await
+    }
+
+    /// Upload the source and post its first media asset with the tags, rating and source in `changes`
+    pub async fn upload_post(
+        &self,
+        source: UploadSource,
+        changes: Query,
+    ) -> Result<WriteOutcome<response::Post>> {
+        let upload = match self.create_upload(source).await? {
+            WriteOutcome::Applied(upload) => upload,
+            WriteOutcome::DryRun(request) => return Ok(WriteOutcome::DryRun(request)),
+        };
+        let upload = self
+            .wait_for_upload(upload.id, UPLOAD_POLL_INTERVAL, UPLOAD_TIMEOUT)
+            .await?;
+        let Some(asset) = upload.active_media_assets().next() else {
+            return Err(Error::Upload {
+                id: upload.id,
+                message: "no media asset to post".to_string(),
+            });
+        };
+        self.create_post(asset.id, changes).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::danbooru::Danbooru;
+    use crate::board::danbooru::Rating;
+    use crate::mock::{fixtures, MockResponse, MockServer};
+
+    #[tokio::test]
+    async fn test_upload_post() {
+        let server = MockServer::start().await;
+        server
+            .mock(Method::POST, "/uploads.json")
+            .respond(MockResponse::json(fixtures::UPLOAD_PENDING))
+            .mount();
+        server
+            .mock(Method::GET, "/uploads/7000001.json")
+            .respond(MockResponse::json(fixtures::UPLOAD))
+            .mount();
+        server
+            .mock(Method::POST, "/posts.json")
+            .respond(MockResponse::json(fixtures::POST))
+            .mount();
+        let client = server.client();
+
+        let mut changes = Query::post_update();
+        changes.tag_string("1girl cat_ears");
+        changes.post_rating(Rating::General);
+        let post = client
+            .upload_post(UploadSource::file("cat.png", b"\x89PNG".to_vec()), changes)
+            .await
+            .unwrap()
+            .applied()
+            .unwrap();
+        assert_eq!(post.id, 8000000);
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 3);
+        assert!(requests[0]
+            .header("content-type")
+            .unwrap()
+            .starts_with("multipart/form-data; boundary="));
+        let body = String::from_utf8_lossy(&requests[0].body);
+        assert!(body.contains("name=\"upload[files][0]\"; filename=\"cat.png\""));
+        assert!(body.contains("\u{FFFD}PNG"));
+        assert_eq!(
+            requests[2].form_param("upload_media_asset_id").as_deref(),
+            Some("9000001")
+        );
+        assert_eq!(
+            requests[2].form_param("post[tag_string]").as_deref(),
+            Some("1girl cat_ears")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_upload_error() {
+        let server = MockServer::start().await;
+        server
+            .mock(Method::GET, "/uploads/7000001.json")
+            .respond(MockResponse::json(fixtures::UPLOAD_PENDING))
+            .respond(MockResponse::json(
+                &fixtures::UPLOAD_PENDING
+                    .replace(
+                        r#""status": "processing""#,
+                        r#""status": "error: Not an image""#,
+                    )
+                    .replace(r#""error": null"#, r#""error": "Not an image""#),
+            ))
+            .mount();
+        let client = server.client();
+
+        let err = client
+            .wait_for_upload(7000001, Duration::from_millis(1), Duration::from_secs(5))
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(err, Error::Upload { id: 7000001, ref message } if message == "Not an image")
+        );
+        assert_eq!(server.requests().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_create_upload_retry() {
+        let server = MockServer::start().await;
+        server
+            .mock(Method::POST, "/uploads.json")
+            .respond(MockResponse::too_many_requests(0))
+            .respond(MockResponse::json(fixtures::UPLOAD_PENDING))
+            .mount();
+        let client = server.client();
+
+        let upload = client
+            .create_upload(UploadSource::file("cat.png", b"\x89PNG".to_vec()))
+            .await
+            .unwrap()
+            .applied()
+            .unwrap();
+        assert_eq!(upload.id, 7000001);
+
+        // the file is sent again with the retry
+        let requests = server.requests();
+        assert_eq!(requests.len(), 2);
+        assert!(String::from_utf8_lossy(&requests[1].body).contains("filename=\"cat.png\""));
+    }
+
+    #[tokio::test]
+    async fn test_upload_dry_run() {
+        let server = MockServer::start().await;
+        let client = server
+            .client_builder(Danbooru::Danbooru)
+            .dry_run(true)
+            .build()
+            .unwrap();
+
+        let outcome = client
+            .upload_post(
+                UploadSource::url("https://www.pixiv.net/artworks/120000000"),
+                Query::post_update(),
+            )
+            .await
+            .unwrap();
+        let WriteOutcome::DryRun(request) = outcome else {
+            panic!("the upload must not be sent");
+        };
+        assert_eq!(request.url.path(), "/uploads.json");
+        assert!(server.requests().is_empty());
+    }
+}
@@ -0,0 +1,299 @@
+use super::{decode, Client};
+use crate::board::danbooru::{response, Endpoint, Query, Vote};
+use crate::board::{BoardQuery, BoardResponse};
+use crate::error::{Error, Result};
+use reqwest::header::{self, HeaderMap, HeaderValue};
+use reqwest::{multipart, Method, Response, Url};
+use std::fmt;
+
+/// File sent in a multipart form
+#[derive(Debug, Clone, PartialEq)]
+pub struct FormFile {
+    /// name of the form field
+    pub field: String,
+    pub file_name: String,
+    pub bytes: Vec<u8>,
+}
+
+/// Write request to the board
+#[derive(Debug, Clone, PartialEq)]
+pub struct WriteRequest {
+    pub method: Method,
+    pub url: Url,
+    /// form fields, sent urlencoded unless there is a file
+    pub form: Vec<(String, String)>,
+    pub file: Option<FormFile>,
+}
+
+impl FormFile {
+    /// multipart form with the fields and the file
+    pub(super) fn multipart(&self, fields: &[(String, String)]) -> multipart::Form {
+        let headers = HeaderMap::from_iter([(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("application/octet-stream"),
+        )]);
+        let part = multipart::Part::bytes(self.bytes.clone())
+            .file_name(self.file_name.clone())
+            .headers(headers);
+        fields
+            .iter()
+            .fold(multipart::Form::new(), |form, (name, value)| {
+                form.text(name.clone(), value.clone())
+            })
+            .part(self.field.clone(), part)
+    }
+
+    /// stands for the multipart body in the cassette key, which cannot read the streamed form
+    pub(super) fn cassette_body(&self, fields: &[(String, String)]) -> Vec<u8> {
+        let form = url::form_urlencoded::Serializer::new(String::new())
+            .extend_pairs(fields)
+            .append_pair(&self.field, &self.file_name)
+            .finish();
+        [form.as_bytes(), b"\n", &self.bytes].concat()
+    }
+}
+
+impl fmt::Display for WriteRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let form = url::form_urlencoded::Serializer::new(String::new())
+            .extend_pairs(&self.form)
+            .finish();
+        write!(f, "{} {} {}", self.method, self.url, form)?;
+        if let Some(file) = &self.file {
+            write!(
+                f,
+                " ({}: {}, {} bytes)",
+                file.field,
+                file.file_name,
+                file.bytes.len()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Result of a write request
+#[derive(Debug, Clone, PartialEq)]
+pub enum WriteOutcome<T> {
+    /// the request was sent and the board returned the response
+    Applied(T),
+    /// the request was not sent because the client is in dry-run mode
+    DryRun(Box<WriteRequest>),
+}
+
+impl<T> WriteOutcome<T> {
+    pub fn is_dry_run(&self) -> bool {
+        matches!(self, WriteOutcome::DryRun(_))
+    }
+
+    /// response of the board, `None` in dry-run mode
+    pub fn applied(self) -> Option<T> {
+        match self {
+            WriteOutcome::Applied(res) => Some(res),
+            WriteOutcome::DryRun(_) => None,
+        }
+    }
+}
+
+/// Danbooru write API, which only describes the requests in dry-run mode
+impl Client {
+    pub(super) fn write_request(
+        &self,
+        endpoint: Endpoint,
+        method: Method,
+        form: Query,
+    ) -> Result<WriteRequest> {
+        Ok(WriteRequest {
+            method,
+            url: self.compose(endpoint, Query::new())?,
+            form: form.pairs(),
+            file: None,
+        })
+    }
+
+    async fn send_write(&self, request: WriteRequest) -> Result<Response> {
+        match &request.file {
+            Some(file) => {
+                self.submit_multipart_raw(request.url, request.method, &request.form, file)
+                    .await
+            }
+            None => {
+                self.submit_raw(request.url, request.method, &request.form)
+                    .await
+            }
+        }
+    }
+
+    pub(super) async fn write<T: BoardResponse>(
+        &self,
+        request: WriteRequest,
+    ) -> Result<WriteOutcome<T>> {
+        if self.dry_run {
+            return Ok(WriteOutcome::DryRun(Box::new(request)));
+        }
+        let res = self.send_write(request).await?;
+        Ok(WriteOutcome::Applied(decode(res).await?))
+    }
+
+    /// for endpoints responding with an empty body
+    async fn write_empty(&self, request: WriteRequest) -> Result<WriteOutcome<()>> {
+        if self.dry_run {
+            return Ok(WriteOutcome::DryRun(Box::new(request)));
+        }
+        let res = self.send_write(request).await?;
+        Error::check_response(res).await?;
+        Ok(WriteOutcome::Applied(()))
+    }
+
+    /// Update the post with the changes built from `Query::post_update`
+    pub async fn update_post(
+        &self,
+        id: i64,
+        changes: Query,
+    ) -> Result<WriteOutcome<response::Post>> {
+        let request = self.write_request(Endpoint::Post(id), Method::PUT, changes)?;
+        self.write(request).await
+    }
+
+    /// Add and remove tags of the post, keeping tags edited by others since the post was fetched
+    pub async fn edit_post_tags(
+        &self,
+        post: &response::Post,
+        add: &[&str],
+        remove: &[&str],
+    ) -> Result<WriteOutcome<response::Post>> {
+        let mut tags = post
+            .tag_string
+            .split_whitespace()
+            .filter(|tag| !remove.contains(tag))
+            .collect::<Vec<_>>();
+        for tag in add {
+            if !tags.contains(tag) {
+                tags.push(tag);
+            }
+        }
+
+        let mut changes = Query::post_update();
+        changes.tag_string(&tags.join(" "));
+        changes.old_tag_string(&post.tag_string);
+        self.update_post(post.id, changes).await
+    }
+
+    /// Vote on the post, replacing the previous vote
+    pub async fn vote(&self, post_id: i64, vote: Vote) -> Result<WriteOutcome<response::PostVote>> {
+        let request = self.write_request(
+            Endpoint::PostVotes(post_id),
+            Method::POST,
+            Query::vote(vote),
+        )?;
+        self.write(request).await
+    }
+
+    /// Remove the vote on the post
+    pub async fn unvote(&self, post_id: i64) -> Result<WriteOutcome<()>> {
+        let request =
+            self.write_request(Endpoint::PostVotes(post_id), Method::DELETE, Query::new())?;
+        self.write_empty(request).await
+    }
+
+    /// Add the post to favorites
+    pub async fn favorite(&self, post_id: i64) -> Result<WriteOutcome<response::Favorite>> {
+        let request =
+            self.write_request(Endpoint::Favorites, Method::POST, Query::favorite(post_id))?;
+        self.write(request).await
+    }
+
+    /// Remove the post from favorites
+    pub async fn unfavorite(&self, post_id: i64) -> Result<WriteOutcome<()>> {
+        let request =
+            self.write_request(Endpoint::Favorite(post_id), Method::DELETE, Query::new())?;
+        self.write_empty(request).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::danbooru::Danbooru;
+    use crate::mock::{fixtures, MockResponse, MockServer};
+
+    fn post() -> response::Post {
+        response::Post::from_str(fixtures::POST).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_edit_post_tags() {
+        let server = MockServer::start().await;
+        server
+            .mock(Method::PUT, "/posts/8000000.json")
+            .respond(MockResponse::json(fixtures::POST))
+            .mount();
+        let client = server.client();
+
+        let post = post();
+        let outcome = client
+            .edit_post_tags(&post, &["cat_girl", "solo"], &["animal_ears"])
+            .await
+            .unwrap();
+        assert_eq!(outcome.applied().unwrap().id, 8000000);
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(
+            requests[0].form_param("post[tag_string]").as_deref(),
+            Some("1girl blue_eyes cat_ears commentary_request example_(artist) hatsune_miku highres solo vocaloid cat_girl")
+        );
+        assert_eq!(
+            requests[0].form_param("post[old_tag_string]"),
+            Some(post.tag_string)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_dry_run() {
+        let server = MockServer::start().await;
+        let client = server
+            .client_builder(Danbooru::Danbooru)
+            .dry_run(true)
+            .build()
+            .unwrap();
+
+        let outcome = client.vote(8000000, Vote::Up).await.unwrap();
+        let WriteOutcome::DryRun(request) = outcome else {
+            panic!("the vote must not be sent");
+        };
+        assert_eq!(request.method, Method::POST);
+        assert_eq!(request.url.path(), "/posts/8000000/votes.json");
+        assert!(request.to_string().ends_with(" score=1"));
+
+        assert!(client.unfavorite(8000000).await.unwrap().is_dry_run());
+        assert!(server.requests().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_favorite() {
+        let server = MockServer::start().await;
+        server
+            .mock(Method::POST, "/favorites.json")
+            .respond(MockResponse::json(fixtures::FAVORITE))
+            .mount();
+        server
+            .mock(Method::DELETE, "/favorites/8000000.json")
+            .respond(MockResponse::status(204))
+            .mount();
+        let client = server.client();
+
+        let favorite = client.favorite(8000000).await.unwrap().applied().unwrap();
+        assert_eq!(favorite.post_id, 8000000);
+        assert_eq!(
+            client.unfavorite(8000000).await.unwrap(),
+            WriteOutcome::Applied(())
+        );
+
+        let requests = server.requests();
+        assert_eq!(
+            requests[0].form_param("post_id").as_deref(),
+            Some("8000000")
+        );
+    }
+}
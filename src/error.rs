@@ -0,0 +1,253 @@
+use crate::client::parse_retry_after;
+use reqwest::{header, Method, StatusCode, Url};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::time::Duration;
+
+/// max length of the response body kept in decode errors
+const SNIPPET_LENGTH: usize = 256;
+
+/// Result type of this crate
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Error type of this crate
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// 404
+    #[error("not found: {0}")]
+    NotFound(Box<HttpError>),
+    /// 401 or 403
+    #[error("unauthorized: {0}")]
+    Unauthorized(Box<HttpError>),
+    /// 429
+    #[error("too many requests: {0}")]
+    TooManyRequests(Box<HttpError>),
+    /// other 4xx
+    #[error("client error: {0}")]
+    ClientError(Box<HttpError>),
+    /// 5xx
+    #[error("server error: {0}")]
+    ServerError(Box<HttpError>),
+    /// error body returned with a successful status
+    #[error("api error: {0}")]
+    Api(ApiError),
+    /// the response body does not match the expected schema
+    #[error("failed to decode response: {source} (body: {snippet})")]
+    Decode {
+        source: serde_json::Error,
+        snippet: String,
+    },
+    /// the request is not recorded in the cassette
+    #[error("no recorded response in the cassette: {method} {url}")]
+    CassetteMiss { method: Method, url: Url },
+    /// the cassette has a response which cannot be replayed
+    #[error("invalid response in the cassette: {0}")]
+    Cassette(String),
+    /// the upload failed or was not processed in time
+    #[error("upload {id} failed: {message}")]
+    Upload { id: i64, message: String },
+    /// network or protocol error
+    #[error(transparent)]
+    Transport(#[from] reqwest::Error),
+    #[error(transparent)]
+    Url(#[from] url::ParseError),
+    #[error(transparent)]
+    InvalidHeader(#[from] header::InvalidHeaderValue),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
+impl Error {
+    /// create a decode error with the beginning of the body
+    pub fn decode(source: serde_json::Error, body: &str) -> Self {
+        let snippet = match body.char_indices().nth(SNIPPET_LENGTH) {
+            Some((i, _)) => format!("{}...", &body[..i]),
+            None => body.to_string(),
+        };
+        Error::Decode { source, snippet }
+    }
+
+    /// create an error from an unsuccessful response
+    pub fn from_status(
+        status: StatusCode,
+        url: Url,
+        body: &str,
+        retry_after: Option<Duration>,
+    ) -> Self {
+        let error = Box::new(HttpError {
+            status,
+            url,
+            api: ApiError::from_body(body),
+            retry_after,
+        });
+
+        match status {
+            StatusCode::NOT_FOUND => Error::NotFound(error),
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => Error::Unauthorized(error),
+            StatusCode::TOO_MANY_REQUESTS => Error::TooManyRequests(error),
+            s if s.is_server_error() => Error::ServerError(error),
+            _ => Error::ClientError(error),
+        }
+    }
+
+    /// create an error from the response if its status is not successful
+    pub async fn check_response(res: reqwest::Response) -> Result<reqwest::Response> {
+        let status = res.status();
+        if status.is_success() {
+            return Ok(res);
+        }
+
+        let url = res.url().clone();
+        let retry_after = res
+            .headers()
+            .get(header::RETRY_AFTER)
+            .and_then(|v| v.to_str().ok())
+            .and_then(parse_retry_after);
+        let body = res.text().await?;
+
+        Err(Error::from_status(status, url, &body, retry_after))
+    }
+
+    /// HTTP status code of the response, if any
+    pub fn status(&self) -> Option<StatusCode> {
+        match self {
+            Error::NotFound(e)
+            | Error::Unauthorized(e)
+            | Error::TooManyRequests(e)
+            | Error::ClientError(e)
+            | Error::ServerError(e) => Some(e.status),
+            Error::Transport(e) => e.status(),
+            _ => None,
+        }
+    }
+
+    pub fn is_not_found(&self) -> bool {
+        matches!(self, Error::NotFound(_))
+    }
+
+    pub fn is_rate_limited(&self) -> bool {
+        matches!(self, Error::TooManyRequests(_))
+    }
+}
+
+/// Unsuccessful HTTP response
+#[derive(Debug, Clone)]
+pub struct HttpError {
+    pub status: StatusCode,
+    pub url: Url,
+    /// error body returned by the board
+    pub api: Option<ApiError>,
+    /// value of the `Retry-After` header
+    pub retry_after: Option<Duration>,
+}
+
+impl fmt::Display for HttpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.status, self.url)?;
+        if let Some(api) = &self.api {
+            write!(f, " ({})", api)?;
+        }
+        Ok(())
+    }
+}
+
+/// Danbooru's JSON error body (`{"success":false,"message":...}`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ApiError {
+    pub success: bool,
+    /// error class name (e.g. `ActiveRecord::RecordNotFound`)
+    pub error: Option<String>,
+    /// `reason` on e621
+    #[serde(alias = "reason")]
+    pub message: Option<String>,
+}
+
+impl ApiError {
+    /// parse the body if it is an error body
+    pub fn from_body(body: &str) -> Option<Self> {
+        serde_json::from_str::<ApiError>(body)
+            .ok()
+            .filter(|e| !e.success)
+    }
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.error, &self.message) {
+            (Some(error), Some(message)) => write!(f, "{}: {}", error, message),
+            (None, Some(message)) => write!(f, "{}", message),
+            (Some(error), None) => write!(f, "{}", error),
+            (None, None) => write!(f, "unknown error"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn url() -> Url {
+        Url::parse("https://danbooru.donmai.us/wiki_pages/foo.json").unwrap()
+    }
+
+    #[test]
+    fn test_from_status() {
+        let body = r#"{"success":false,"error":"ActiveRecord::RecordNotFound","message":"That record was not found."}"#;
+
+        let err = Error::from_status(StatusCode::NOT_FOUND, url(), body, None);
+        assert!(err.is_not_found());
+        assert_eq!(err.status(), Some(StatusCode::NOT_FOUND));
+        match err {
+            Error::NotFound(e) => {
+                let api = e.api.unwrap();
+                assert_eq!(api.error.unwrap(), "ActiveRecord::RecordNotFound");
+                assert_eq!(api.message.unwrap(), "That record was not found.");
+            }
+            _ => unreachable!(),
+        }
+
+        let err = Error::from_status(StatusCode::FORBIDDEN, url(), "", None);
+        assert!(matches!(err, Error::Unauthorized(_)));
+
+        let err = Error::from_status(
+            StatusCode::TOO_MANY_REQUESTS,
+            url(),
+            "<html></html>",
+            Some(Duration::from_secs(2)),
+        );
+        assert!(err.is_rate_limited());
+
+        let err = Error::from_status(StatusCode::UNPROCESSABLE_ENTITY, url(), "", None);
+        assert!(matches!(err, Error::ClientError(_)));
+
+        let err = Error::from_status(StatusCode::BAD_GATEWAY, url(), "", None);
+        assert!(matches!(err, Error::ServerError(_)));
+    }
+
+    #[test]
+    fn test_decode_snippet() {
+        let body = "あ".repeat(SNIPPET_LENGTH + 10);
+        let source = serde_json::from_str::<i64>(&body).unwrap_err();
+
+        match Error::decode(source, &body) {
+            Error::Decode { snippet, .. } => {
+                assert_eq!(snippet, format!("{}...", "あ".repeat(SNIPPET_LENGTH)));
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_api_error_from_body() {
+        let body =
+            r#"{"success":false,"message":"You cannot search for more than 2 tags at a time."}"#;
+        let api = ApiError::from_body(body).unwrap();
+        assert_eq!(
+            api.to_string(),
+            "You cannot search for more than 2 tags at a time."
+        );
+
+        assert!(ApiError::from_body(r#"{"success":true}"#).is_none());
+        assert!(ApiError::from_body(r#"[]"#).is_none());
+    }
+}
@@ -1,5 +1,12 @@
 pub mod board;
 pub mod client;
+pub mod error;
+pub mod model;
 pub mod tags;
 
-mod test_utils;
+#[cfg(any(test, feature = "mock"))]
+pub mod mock;
+
+// -- re-exports
+
+pub use error::{Error, Result};
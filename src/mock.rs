@@ -0,0 +1,452 @@
+//! Local HTTP mock server replaying recorded responses, for testing without network access.
+//!
+//! Only plain HTTP/1.1 with `Content-Length` bodies is supported, which is what [`Client`]
+//! sends when it is built with [`HttpVersion::Http1`] or the default [`HttpVersion::Negotiate`].
+
+use crate::board::danbooru::Danbooru;
+use crate::board::BoardApi;
+use crate::client::{Client, ClientBuilder, HttpVersion, RateLimiter, RetryPolicy};
+use reqwest::{Method, StatusCode};
+use std::collections::VecDeque;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinHandle;
+
+/// Recorded board responses
+pub mod fixtures {
+    /// /posts.json?tags=2girls cat_ears rating:g filetype:webp score:10..&limit=3
+    pub const POSTS: &str = include_str!("../tests/fixtures/danbooru/posts.json");
+    /// /posts/{id}.json
+    pub const POST: &str = include_str!("../tests/fixtures/danbooru/post.json");
+    /// /wiki_pages/{title}.json
+    pub const WIKI_PAGE: &str = include_str!("../tests/fixtures/danbooru/wiki_page.json");
+    /// /profile.json
+    pub const PROFILE: &str = include_str!("../tests/fixtures/danbooru/profile.json");
+    /// /tags.json?search[name_matches]=cat_*
+    pub const TAGS: &str = include_str!("../tests/fixtures/danbooru/tags.json");
+    /// /tag_aliases.json
+    pub const TAG_ALIASES: &str = include_str!("../tests/fixtures/danbooru/tag_aliases.json");
+    /// /tag_implications.json
+    pub const TAG_IMPLICATIONS: &str =
+        include_str!("../tests/fixtures/danbooru/tag_implications.json");
+    /// /related_tag.json?query=cat_ears
+    pub const RELATED_TAG: &str = include_str!("../tests/fixtures/danbooru/related_tag.json");
+    /// /autocomplete.json?search[query]=cat_ear&search[type]=tag_query
+    pub const AUTOCOMPLETE: &str = include_str!("../tests/fixtures/danbooru/autocomplete.json");
+    /// /artists.json?search[any_name_matches]=*
+    pub const ARTISTS: &str = include_str!("../tests/fixtures/danbooru/artists.json");
+    /// /artist_urls.json?search[url_matches]=https://twitter.com/example
+    pub const ARTIST_URLS: &str = include_str!("../tests/fixtures/danbooru/artist_urls.json");
+    /// /pools/{id}.json
+    pub const POOL: &str = include_str!("../tests/fixtures/danbooru/pool.json");
+    /// /favorite_groups.json
+    pub const FAVORITE_GROUPS: &str =
+        include_str!("../tests/fixtures/danbooru/favorite_groups.json");
+    /// /comments.json?search[post_id]=8000000
+    pub const COMMENTS: &str = include_str!("../tests/fixtures/danbooru/comments.json");
+    /// /notes.json?search[post_id]=8000000
+    pub const NOTES: &str = include_str!("../tests/fixtures/danbooru/notes.json");
+    /// /artist_commentaries.json?search[post_id]=8000000
+    pub const ARTIST_COMMENTARIES: &str =
+        include_str!("../tests/fixtures/danbooru/artist_commentaries.json");
+    /// /post_versions.json
+    pub const POST_VERSIONS: &str = include_str!("../tests/fixtures/danbooru/post_versions.json");
+    /// /wiki_page_versions.json
+    pub const WIKI_PAGE_VERSIONS: &str =
+        include_str!("../tests/fixtures/danbooru/wiki_page_versions.json");
+    /// /counts/posts.json
+    pub const COUNTS: &str = include_str!("../tests/fixtures/danbooru/counts.json");
+    /// /posts/{id}/votes.json
+    pub const POST_VOTE: &str = include_str!("../tests/fixtures/danbooru/post_vote.json");
+    /// /favorites.json
+    pub const FAVORITE: &str = include_str!("../tests/fixtures/danbooru/favorite.json");
+    /// /uploads/{id}.json, processed
+    pub const UPLOAD: &str = include_str!("../tests/fixtures/danbooru/upload.json");
+    /// /uploads.json, just created
+    pub const UPLOAD_PENDING: &str = include_str!("../tests/fixtures/danbooru/upload_pending.json");
+    /// moebooru /post.json
+    pub const MOEBOORU_POSTS: &str = include_str!("../tests/fixtures/moebooru/posts.json");
+    /// moebooru /pool/show/{id}.json
+    pub const MOEBOORU_POOL: &str = include_str!("../tests/fixtures/moebooru/pool.json");
+    /// gelbooru posts
+    pub const GELBOORU_POSTS: &str = include_str!("../tests/fixtures/gelbooru/posts.json");
+    /// e621 /posts.json
+    pub const E621_POSTS: &str = include_str!("../tests/fixtures/e621/posts.json");
+    /// 404 error body
+    pub const NOT_FOUND: &str = include_str!("../tests/fixtures/danbooru/not_found.json");
+    /// 429 error body
+    pub const TOO_MANY_REQUESTS: &str =
+        include_str!("../tests/fixtures/danbooru/too_many_requests.json");
+}
+
+/// Response returned by the mock server
+#[derive(Debug, Clone)]
+pub struct MockResponse {
+    pub status: StatusCode,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl MockResponse {
+    /// empty response with the status
+    pub fn status(status: u16) -> Self {
+        MockResponse {
+            status: StatusCode::from_u16(status).expect("invalid status code"),
+            headers: vec![],
+            body: vec![],
+        }
+    }
+
+    /// 200 response with the JSON body
+    pub fn json(body: &str) -> Self {
+        MockResponse::status(200).with_json(body)
+    }
+
+    /// set JSON body
+    pub fn with_json(mut self, body: &str) -> Self {
+        self.headers
+            .retain(|(k, _)| !k.eq_ignore_ascii_case("content-type"));
+        self.headers.push((
+            "content-type".to_string(),
+            "application/json; charset=utf-8".to_string(),
+        ));
+        self.body = body.as_bytes().to_vec();
+        self
+    }
+
+    /// set raw body
+    pub fn with_body(mut self, body: &[u8]) -> Self {
+        self.body = body.to_vec();
+        self
+    }
+
+    /// add a header
+    pub fn with_header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// Danbooru's 404 response
+    pub fn not_found() -> Self {
+        MockResponse::status(404).with_json(fixtures::NOT_FOUND)
+    }
+
+    /// Danbooru's 429 response
+    pub fn too_many_requests(retry_after: u64) -> Self {
+        MockResponse::status(429)
+            .with_json(fixtures::TOO_MANY_REQUESTS)
+            .with_header("retry-after", &retry_after.to_string())
+    }
+}
+
+/// Request received by the mock server
+#[derive(Debug, Clone)]
+pub struct RecordedRequest {
+    pub method: Method,
+    pub path: String,
+    pub query: Vec<(String, String)>,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl RecordedRequest {
+    /// get the first query parameter with the key
+    pub fn param(&self, key: &str) -> Option<&str> {
+        self.query
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// get the first urlencoded form parameter of the body with the key
+    pub fn form_param(&self, key: &str) -> Option<String> {
+        url::form_urlencoded::parse(&self.body)
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.into_owned())
+    }
+
+    /// get the first header with the name
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+#[derive(Debug)]
+struct Route {
+    method: Method,
+    path: String,
+    query: Vec<(String, String)>,
+    responses: VecDeque<MockResponse>,
+}
+
+impl Route {
+    fn matches(&self, req: &RecordedRequest) -> bool {
+        self.method == req.method
+            && self.path == req.path
+            && self.query.iter().all(|pair| req.query.contains(pair))
+    }
+
+    /// responses are returned in order, and the last one is repeated
+    fn next_response(&mut self) -> MockResponse {
+        if self.responses.len() > 1 {
+            self.responses.pop_front().unwrap()
+        } else {
+            self.responses.front().cloned().unwrap()
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct State {
+    routes: Vec<Route>,
+    requests: Vec<RecordedRequest>,
+}
+
+/// Route registration
+pub struct MockBuilder<'a> {
+    server: &'a MockServer,
+    route: Route,
+}
+
+impl MockBuilder<'_> {
+    /// match only requests with the query parameter
+    pub fn query(mut self, key: &str, value: &str) -> Self {
+        self.route.query.push((key.to_string(), value.to_string()));
+        self
+    }
+
+    /// respond with the response, can be called multiple times to return them in order
+    pub fn respond(mut self, response: MockResponse) -> Self {
+        self.route.responses.push_back(response);
+        self
+    }
+
+    /// register the route
+    pub fn mount(self) {
+        assert!(
+            !self.route.responses.is_empty(),
+            "route {} {} has no response",
+            self.route.method,
+            self.route.path
+        );
+        // later routes take precedence
+        self.server
+            .state
+            .lock()
+            .unwrap()
+            .routes
+            .insert(0, self.route);
+    }
+}
+
+/// Local HTTP/1.1 server replaying registered responses
+#[derive(Debug)]
+pub struct MockServer {
+    addr: SocketAddr,
+    state: Arc<Mutex<State>>,
+    handle: JoinHandle<()>,
+}
+
+impl MockServer {
+    /// Start a server on a random local port
+    pub async fn start() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("failed to bind mock server");
+        let addr = listener.local_addr().unwrap();
+        let state = Arc::new(Mutex::new(State::default()));
+
+        let cloned_state = state.clone();
+        let handle = tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                tokio::spawn(serve(stream, cloned_state.clone()));
+            }
+        });
+
+        MockServer {
+            addr,
+            state,
+            handle,
+        }
+    }
+
+    /// base url of the server
+    pub fn url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    /// register a route
+    pub fn mock(&self, method: Method, path: &str) -> MockBuilder<'_> {
+        MockBuilder {
+            server: self,
+            route: Route {
+                method,
+                path: path.to_string(),
+                query: vec![],
+                responses: VecDeque::new(),
+            },
+        }
+    }
+
+    /// requests received so far
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.state.lock().unwrap().requests.clone()
+    }
+
+    /// client builder pointed at this server, without rate limiting and with fast retries
+    pub fn client_builder<B: BoardApi>(&self, board: B) -> ClientBuilder<B> {
+        Client::builder(board)
+            .base_url(&self.url())
+            .http_version(HttpVersion::Http1)
+            .rate_limiter(RateLimiter::unlimited())
+            .retry(
+                RetryPolicy::default()
+                    .base_delay(Duration::from_millis(1))
+                    .max_delay(Duration::from_millis(10)),
+            )
+    }
+
+    /// anonymous danbooru client pointed at this server
+    pub fn client(&self) -> Client {
+        self.client_builder(Danbooru::Danbooru)
+            .build()
+            .expect("failed to build mock client")
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
+
+async fn serve(stream: TcpStream, state: Arc<Mutex<State>>) {
+    let mut reader = BufReader::new(stream);
+    while let Some(req) = read_request(&mut reader).await {
+        let res = {
+            let mut state = state.lock().unwrap();
+            let res = state
+                .routes
+                .iter_mut()
+                .find(|route| route.matches(&req))
+                .map(|route| route.next_response())
+                .unwrap_or_else(MockResponse::not_found);
+            state.requests.push(req);
+            res
+        };
+
+        if write_response(reader.get_mut(), &res).await.is_err() {
+            break;
+        }
+    }
+}
+
+async fn read_request(reader: &mut BufReader<TcpStream>) -> Option<RecordedRequest> {
+    let mut line = String::new();
+    reader.read_line(&mut line).await.ok()?;
+    let mut parts = line.split_whitespace();
+    let method = Method::from_bytes(parts.next()?.as_bytes()).ok()?;
+    let target = parts.next()?.to_string();
+
+    let mut headers = vec![];
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line).await.ok()? == 0 {
+            return None;
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        let (name, value) = line.split_once(':')?;
+        headers.push((name.trim().to_lowercase(), value.trim().to_string()));
+    }
+
+    let length = headers
+        .iter()
+        .find(|(k, _)| k == "content-length")
+        .and_then(|(_, v)| v.parse::<usize>().ok())
+        .unwrap_or(0);
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body).await.ok()?;
+
+    let (path, query) = target.split_once('?').unwrap_or((&target, ""));
+    let query = url::form_urlencoded::parse(query.as_bytes())
+        .into_owned()
+        .collect();
+
+    Some(RecordedRequest {
+        method,
+        path: path.to_string(),
+        query,
+        headers,
+        body,
+    })
+}
+
+async fn write_response(stream: &mut TcpStream, res: &MockResponse) -> std::io::Result<()> {
+    let mut head = format!(
+        "HTTP/1.1 {} {}\r\ncontent-length: {}\r\n",
+        res.status.as_u16(),
+        res.status.canonical_reason().unwrap_or(""),
+        res.body.len()
+    );
+    for (name, value) in &res.headers {
+        head.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    head.push_str("\r\n");
+
+    stream.write_all(head.as_bytes()).await?;
+    stream.write_all(&res.body).await?;
+    stream.flush().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_mock_server() {
+        let server = MockServer::start().await;
+        server
+            .mock(Method::GET, "/posts.json")
+            .respond(MockResponse::status(503))
+            .respond(MockResponse::json(fixtures::POSTS))
+            .mount();
+        // later routes take precedence
+        server
+            .mock(Method::GET, "/posts.json")
+            .query("page", "2")
+            .respond(MockResponse::json("[]"))
+            .mount();
+
+        let http = reqwest::Client::new();
+        let url = format!("{}/posts.json?tags=cat_ears&page=2", server.url());
+        let res = http.get(&url).send().await.unwrap();
+        assert_eq!(res.text().await.unwrap(), "[]");
+
+        let url = format!("{}/posts.json?tags=cat_ears", server.url());
+        let res = http.get(&url).send().await.unwrap();
+        assert_eq!(res.status(), StatusCode::SERVICE_UNAVAILABLE);
+        let res = http.get(&url).send().await.unwrap();
+        assert_eq!(res.text().await.unwrap(), fixtures::POSTS);
+        let res = http.get(&url).send().await.unwrap();
+        assert_eq!(res.text().await.unwrap(), fixtures::POSTS);
+
+        let url = format!("{}/unknown.json", server.url());
+        let res = http.get(&url).send().await.unwrap();
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 5);
+        assert_eq!(requests[0].param("tags"), Some("cat_ears"));
+        assert_eq!(requests[0].param("page"), Some("2"));
+        assert_eq!(requests[4].path, "/unknown.json");
+    }
+}
@@ -0,0 +1,432 @@
+use crate::board::{danbooru, e621, gelbooru, moebooru, BoardApi};
+use chrono::{DateTime, FixedOffset};
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// `created_at` format of Gelbooru (e.g. `Sat Aug 03 12:34:56 -0500 2024`)
+const GELBOORU_DATE_FORMAT: &str = "%a %b %d %H:%M:%S %z %Y";
+
+/// Rating normalized to Danbooru's ratings
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Rating {
+    General,
+    Sensitive,
+    Questionable,
+    Explicit,
+}
+
+impl fmt::Display for Rating {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Rating::General => "general",
+            Rating::Sensitive => "sensitive",
+            Rating::Questionable => "questionable",
+            Rating::Explicit => "explicit",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+/// Tag category shared by all boards
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum TagCategory {
+    General,
+    Artist,
+    Contributor,
+    Copyright,
+    Character,
+    Species,
+    Meta,
+    Lore,
+    Invalid,
+    /// boards which do not return categories with posts
+    Unknown,
+}
+
+impl fmt::Display for TagCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            TagCategory::General => "general",
+            TagCategory::Artist => "artist",
+            TagCategory::Contributor => "contributor",
+            TagCategory::Copyright => "copyright",
+            TagCategory::Character => "character",
+            TagCategory::Species => "species",
+            TagCategory::Meta => "meta",
+            TagCategory::Lore => "lore",
+            TagCategory::Invalid => "invalid",
+            TagCategory::Unknown => "unknown",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+impl From<danbooru::TagCategory> for TagCategory {
+    fn from(category: danbooru::TagCategory) -> Self {
+        match category {
+            danbooru::TagCategory::General => TagCategory::General,
+            danbooru::TagCategory::Artist => TagCategory::Artist,
+            danbooru::TagCategory::Copyright => TagCategory::Copyright,
+            danbooru::TagCategory::Character => TagCategory::Character,
+            danbooru::TagCategory::Meta => TagCategory::Meta,
+        }
+    }
+}
+
+/// Post normalized from the post of any board
+#[derive(Debug, Clone, PartialEq)]
+pub struct Post {
+    pub id: i64,
+    /// name of the board the post was fetched from, see `BoardApi::name`
+    pub board: String,
+
+    // file
+    /// missing if the post is banned or hidden
+    pub file_url: Option<String>,
+    pub file_ext: Option<String>,
+    pub file_size: Option<i64>,
+    pub width: i64,
+    pub height: i64,
+    pub md5: Option<String>,
+
+    pub rating: Rating,
+    pub score: i64,
+    pub source: Option<String>,
+    /// tags by category, in the order returned by the board
+    pub tags: BTreeMap<TagCategory, Vec<String>>,
+    pub created_at: Option<DateTime<FixedOffset>>,
+    pub parent_id: Option<i64>,
+}
+
+impl Post {
+    /// normalize the post fetched from the board
+    pub fn from_native<P: NativePost>(post: P, board: &P::Board) -> Self {
+        post.into_post(board)
+    }
+
+    /// tags of the category
+    pub fn tags(&self, category: TagCategory) -> &[String] {
+        self.tags
+            .get(&category)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// all tags regardless of the category
+    pub fn all_tags(&self) -> impl Iterator<Item = &str> {
+        self.tags.values().flatten().map(String::as_str)
+    }
+}
+
+/// Post of a board engine which can be normalized to [`Post`]
+///
+/// the engine is shared by several boards, so the board the post was fetched from is required
+pub trait NativePost {
+    /// engine of the boards returning the post
+    type Board: BoardApi;
+
+    fn into_post(self, board: &Self::Board) -> Post;
+}
+
+/// tags by category, skipping empty categories
+fn group_tags<'a>(
+    tags: impl IntoIterator<Item = (TagCategory, &'a str)>,
+) -> BTreeMap<TagCategory, Vec<String>> {
+    let mut groups = BTreeMap::new();
+    for (category, tags) in tags {
+        let tags = tags
+            .split_whitespace()
+            .map(str::to_string)
+            .collect::<Vec<_>>();
+        if !tags.is_empty() {
+            groups.entry(category).or_insert_with(Vec::new).extend(tags);
+        }
+    }
+    groups
+}
+
+fn non_empty(source: String) -> Option<String> {
+    Some(source).filter(|s| !s.is_empty())
+}
+
+/// extension of the file in the url
+fn url_ext(url: &str) -> Option<String> {
+    let path = url.split(['?', '#']).next()?;
+    let name = path.rsplit('/').next()?;
+    name.rsplit_once('.').map(|(_, ext)| ext.to_lowercase())
+}
+
+impl NativePost for danbooru::response::Post {
+    type Board = danbooru::Danbooru;
+
+    fn into_post(self, board: &danbooru::Danbooru) -> Post {
+        let tags = group_tags([
+            (TagCategory::General, self.tag_string_general.as_str()),
+            (TagCategory::Artist, &self.tag_string_artist),
+            (TagCategory::Copyright, &self.tag_string_copyright),
+            (TagCategory::Character, &self.tag_string_character),
+            (TagCategory::Meta, &self.tag_string_meta),
+        ]);
+
+        Post {
+            id: self.id,
+            board: board.name().to_string(),
+            file_url: self.file_url,
+            file_ext: Some(self.file_ext.to_string()),
+            file_size: Some(self.file_size),
+            width: self.image_width,
+            height: self.image_height,
+            md5: self.md5,
+            rating: match self.rating {
+                danbooru::Rating::General => Rating::General,
+                danbooru::Rating::Sensitive => Rating::Sensitive,
+                danbooru::Rating::Questionable => Rating::Questionable,
+                danbooru::Rating::Explicit => Rating::Explicit,
+            },
+            score: self.score,
+            source: non_empty(self.source),
+            tags,
+            created_at: DateTime::parse_from_rfc3339(&self.created_at).ok(),
+            parent_id: self.parent_id,
+        }
+    }
+}
+
+impl NativePost for moebooru::response::Post {
+    type Board = moebooru::Moebooru;
+
+    fn into_post(self, board: &moebooru::Moebooru) -> Post {
+        Post {
+            id: self.id,
+            board: board.name().to_string(),
+            file_ext: self
+                .file_ext
+                .or_else(|| self.file_url.as_deref().and_then(url_ext)),
+            file_url: self.file_url,
+            file_size: Some(self.file_size),
+            width: self.width,
+            height: self.height,
+            md5: Some(self.md5),
+            rating: match self.rating {
+                moebooru::Rating::Safe => Rating::General,
+                moebooru::Rating::Questionable => Rating::Questionable,
+                moebooru::Rating::Explicit => Rating::Explicit,
+            },
+            score: self.score,
+            source: non_empty(self.source),
+            tags: group_tags([(TagCategory::Unknown, self.tags.as_str())]),
+            created_at: DateTime::from_timestamp(self.created_at, 0).map(|t| t.fixed_offset()),
+            parent_id: self.parent_id,
+        }
+    }
+}
+
+impl NativePost for gelbooru::response::Post {
+    type Board = gelbooru::Gelbooru;
+
+    fn into_post(self, board: &gelbooru::Gelbooru) -> Post {
+        let parent_id = self.parent();
+        // older sites do not return the url, but serve the image under the host
+        let file_url = self.file_url.clone().unwrap_or_else(|| {
+            format!(
+                "{}{}",
+                board.host().trim_end_matches('/'),
+                self.image_path()
+            )
+        });
+
+        Post {
+            id: self.id,
+            board: board.name().to_string(),
+            file_ext: url_ext(&self.image),
+            file_url: Some(file_url),
+            file_size: None,
+            width: self.width,
+            height: self.height,
+            md5: Some(self.md5),
+            rating: match self.rating {
+                gelbooru::Rating::General => Rating::General,
+                gelbooru::Rating::Sensitive => Rating::Sensitive,
+                gelbooru::Rating::Questionable => Rating::Questionable,
+                gelbooru::Rating::Explicit => Rating::Explicit,
+            },
+            score: self.score.unwrap_or_default(),
+            source: non_empty(self.source),
+            tags: group_tags([(TagCategory::Unknown, self.tags.as_str())]),
+            created_at: self
+                .created_at
+                .as_deref()
+                .and_then(|s| DateTime::parse_from_str(s, GELBOORU_DATE_FORMAT).ok()),
+            parent_id,
+        }
+    }
+}
+
+impl NativePost for e621::response::Post {
+    type Board = e621::E621;
+
+    fn into_post(self, board: &e621::E621) -> Post {
+        let tags = [
+            (TagCategory::General, self.tags.general),
+            (TagCategory::Artist, self.tags.artist),
+            (TagCategory::Contributor, self.tags.contributor),
+            (TagCategory::Copyright, self.tags.copyright),
+            (TagCategory::Character, self.tags.character),
+            (TagCategory::Species, self.tags.species),
+            (TagCategory::Meta, self.tags.meta),
+            (TagCategory::Lore, self.tags.lore),
+            (TagCategory::Invalid, self.tags.invalid),
+        ]
+        .into_iter()
+        .filter(|(_, tags)| !tags.is_empty())
+        .collect();
+
+        Post {
+            id: self.id,
+            board: board.name().to_string(),
+            file_url: self.file.url,
+            file_ext: Some(self.file.ext),
+            file_size: Some(self.file.size),
+            width: self.file.width,
+            height: self.file.height,
+            md5: Some(self.file.md5),
+            rating: match self.rating {
+                e621::Rating::Safe => Rating::General,
+                e621::Rating::Questionable => Rating::Questionable,
+                e621::Rating::Explicit => Rating::Explicit,
+            },
+            score: self.score.total,
+            source: self.sources.into_iter().next(),
+            tags,
+            created_at: DateTime::parse_from_rfc3339(&self.created_at).ok(),
+            parent_id: self.relationships.parent_id,
+        }
+    }
+}
+
+// posts of the default board of the engine, use `Post::from_native` for the other boards
+
+impl From<danbooru::response::Post> for Post {
+    fn from(post: danbooru::response::Post) -> Self {
+        Post::from_native(post, &danbooru::Danbooru::default())
+    }
+}
+
+impl From<moebooru::response::Post> for Post {
+    fn from(post: moebooru::response::Post) -> Self {
+        Post::from_native(post, &moebooru::Moebooru::default())
+    }
+}
+
+impl From<gelbooru::response::Post> for Post {
+    fn from(post: gelbooru::response::Post) -> Self {
+        Post::from_native(post, &gelbooru::Gelbooru::default())
+    }
+}
+
+impl From<e621::response::Post> for Post {
+    fn from(post: e621::response::Post) -> Self {
+        Post::from_native(post, &e621::E621::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::BoardResponse;
+
+    #[test]
+    fn test_from_danbooru() {
+        let post = danbooru::response::Post::from_str(include_str!(
+            "../tests/fixtures/danbooru/post.json"
+        ))
+        .unwrap();
+        let tag_string = post.tag_string.clone();
+
+        let post = Post::from_native(post, &danbooru::Danbooru::Safebooru);
+        assert_eq!(post.board, "safebooru");
+        assert_eq!(post.tags(TagCategory::Character), ["hatsune_miku"]);
+        assert_eq!(
+            post.all_tags().count(),
+            tag_string.split_whitespace().count()
+        );
+        assert!(post.tags(TagCategory::Species).is_empty());
+        assert!(post.created_at.is_some());
+    }
+
+    #[test]
+    fn test_from_moebooru() {
+        let posts = moebooru::response::Posts::from_str(include_str!(
+            "../tests/fixtures/moebooru/posts.json"
+        ))
+        .unwrap();
+
+        let post = Post::from_native(posts[0].clone(), &moebooru::Moebooru::Konachan);
+        assert_eq!(post.board, "konachan");
+        assert_eq!(
+            post.all_tags().collect::<Vec<_>>(),
+            posts[0].tags().collect::<Vec<_>>()
+        );
+        assert!(post.tags(TagCategory::General).is_empty());
+        assert_eq!(post.created_at.unwrap().timestamp(), posts[0].created_at);
+    }
+
+    #[test]
+    fn test_from_gelbooru() {
+        let posts = gelbooru::response::Posts::from_str(include_str!(
+            "../tests/fixtures/gelbooru/posts.json"
+        ))
+        .unwrap();
+
+        let post = Post::from(posts[0].clone());
+        assert_eq!(post.board, "gelbooru");
+        assert_eq!(post.file_ext.as_deref(), Some("jpg"));
+        assert_eq!(
+            post.created_at.unwrap().to_rfc3339(),
+            "2024-08-03T12:34:56-05:00"
+        );
+
+        let posts = gelbooru::response::Posts::from_str(include_str!(
+            "../tests/fixtures/gelbooru/posts_legacy.json"
+        ))
+        .unwrap();
+
+        let post = Post::from_native(
+            posts[0].clone(),
+            &gelbooru::Gelbooru::new("https://safebooru.org"),
+        );
+        assert_eq!(
+            post.file_url.as_deref(),
+            Some("https://safebooru.org/images/1234/0123456789abcdef0123456789abcdef.jpg")
+        );
+        assert_eq!(post.board, "safebooru.org");
+    }
+
+    #[test]
+    fn test_from_e621() {
+        let posts =
+            e621::response::Posts::from_str(include_str!("../tests/fixtures/e621/posts.json"))
+                .unwrap();
+
+        let post = Post::from(posts[0].clone());
+        assert_eq!(post.board, "e621");
+        assert_eq!(post.score, 95);
+        assert_eq!(post.rating, Rating::General);
+        assert_eq!(post.tags(TagCategory::Species), ["canine", "wolf"]);
+        assert_eq!(post.all_tags().count(), 7);
+
+        let post = Post::from_native(posts[1].clone(), &e621::E621::E926);
+        assert_eq!(post.board, "e926");
+        assert!(post.file_url.is_none());
+        assert_eq!(post.parent_id, Some(4800001));
+    }
+
+    #[test]
+    fn test_url_ext() {
+        assert_eq!(
+            url_ext("https://cdn.donmai.us/original/ab/cd/abcd.PNG?download=1"),
+            Some("png".to_string())
+        );
+        assert_eq!(url_ext("https://example.com/images/abcd"), None);
+    }
+}
@@ -1,6 +1,12 @@
+pub mod graph;
+
 use anyhow::Result;
 use regex::{Regex, RegexBuilder};
 
+// -- re-exports
+
+pub use graph::TagGraph;
+
 /// build a regex from tags
 pub fn build_tags_regex(tags: &[&str]) -> Result<Regex> {
     let tags = tags
@@ -0,0 +1,174 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// Tag aliases and implications
+#[derive(Debug, Clone, Default)]
+pub struct TagGraph {
+    /// antecedent -> consequent
+    aliases: HashMap<String, String>,
+    /// antecedent -> consequents
+    implications: HashMap<String, HashSet<String>>,
+}
+
+impl TagGraph {
+    pub fn new() -> Self {
+        TagGraph::default()
+    }
+
+    /// `antecedent` is an alias of `consequent`
+    pub fn add_alias(&mut self, antecedent: &str, consequent: &str) {
+        self.aliases
+            .insert(antecedent.to_string(), consequent.to_string());
+    }
+
+    /// `antecedent` implies `consequent`
+    pub fn add_implication(&mut self, antecedent: &str, consequent: &str) {
+        self.implications
+            .entry(antecedent.to_string())
+            .or_default()
+            .insert(consequent.to_string());
+    }
+
+    /// merge another graph into this one
+    pub fn extend(&mut self, other: TagGraph) {
+        self.aliases.extend(other.aliases);
+        for (antecedent, consequents) in other.implications {
+            self.implications
+                .entry(antecedent)
+                .or_default()
+                .extend(consequents);
+        }
+    }
+
+    pub fn aliases_len(&self) -> usize {
+        self.aliases.len()
+    }
+
+    pub fn implications_len(&self) -> usize {
+        self.implications.values().map(HashSet::len).sum()
+    }
+
+    /// resolve the tag to the tag it is aliased to
+    pub fn canonical<'a>(&'a self, tag: &'a str) -> &'a str {
+        let mut current = tag;
+        let mut visited = HashSet::new();
+        while let Some(next) = self.aliases.get(current) {
+            // broken data may contain cycles
+            if !visited.insert(current) {
+                break;
+            }
+            current = next;
+        }
+        current
+    }
+
+    /// canonicalize tags, removing duplicates while keeping the order
+    pub fn canonicalize(&self, tags: Vec<String>) -> Vec<String> {
+        let mut seen = HashSet::new();
+        tags.iter()
+            .map(|tag| self.canonical(tag).to_string())
+            .filter(|tag| seen.insert(tag.clone()))
+            .collect()
+    }
+
+    /// all tags implied by the tag, directly or transitively
+    pub fn implied(&self, tag: &str) -> HashSet<String> {
+        let mut implied = HashSet::new();
+        let mut queue = VecDeque::from([self.canonical(tag).to_string()]);
+        while let Some(tag) = queue.pop_front() {
+            let Some(consequents) = self.implications.get(&tag) else {
+                continue;
+            };
+            for consequent in consequents {
+                let consequent = self.canonical(consequent);
+                if implied.insert(consequent.to_string()) {
+                    queue.push_back(consequent.to_string());
+                }
+            }
+        }
+        implied
+    }
+
+    /// whether `tag` implies `other`
+    pub fn implies(&self, tag: &str, other: &str) -> bool {
+        self.implied(tag).contains(self.canonical(other))
+    }
+
+    /// remove tags which are implied by other tags in the list
+    pub fn remove_implied(&self, tags: Vec<String>) -> Vec<String> {
+        let implied = tags
+            .iter()
+            .flat_map(|tag| self.implied(tag))
+            .collect::<HashSet<_>>();
+        tags.into_iter()
+            .filter(|tag| !implied.contains(self.canonical(tag)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn graph() -> TagGraph {
+        let mut graph = TagGraph::new();
+        graph.add_alias("cat_ear", "cat_ears");
+        graph.add_alias("nekomimi", "cat_ear");
+        graph.add_implication("cat_ears", "animal_ears");
+        graph.add_implication("cat_girl", "cat_ears");
+        graph.add_implication("cat_girl", "animal_ear_fluff");
+        graph
+    }
+
+    #[test]
+    fn test_canonical() {
+        let graph = graph();
+
+        assert_eq!(graph.canonical("nekomimi"), "cat_ears");
+        assert_eq!(graph.canonical("cat_ears"), "cat_ears");
+        assert_eq!(graph.canonical("1girl"), "1girl");
+
+        let tags = vec!["nekomimi", "1girl", "cat_ears"]
+            .into_iter()
+            .map(String::from)
+            .collect();
+        assert_eq!(graph.canonicalize(tags), vec!["cat_ears", "1girl"]);
+    }
+
+    #[test]
+    fn test_alias_cycle() {
+        let mut graph = TagGraph::new();
+        graph.add_alias("a", "b");
+        graph.add_alias("b", "a");
+
+        assert_eq!(graph.canonical("a"), "a");
+    }
+
+    #[test]
+    fn test_implied() {
+        let graph = graph();
+
+        let implied = graph.implied("cat_girl");
+        assert_eq!(implied.len(), 3);
+        assert!(implied.contains("cat_ears"));
+        assert!(implied.contains("animal_ears"));
+        assert!(implied.contains("animal_ear_fluff"));
+
+        assert!(graph.implies("nekomimi", "animal_ears"));
+        assert!(!graph.implies("animal_ears", "cat_ears"));
+        assert!(graph.implied("1girl").is_empty());
+    }
+
+    #[test]
+    fn test_remove_implied() {
+        let graph = graph();
+
+        let tags = "1girl animal_ears cat_ears cat_girl solo"
+            .split(' ')
+            .map(String::from)
+            .collect();
+        assert_eq!(
+            graph.remove_implied(tags),
+            vec!["1girl", "cat_girl", "solo"]
+        );
+    }
+}
@@ -1,19 +0,0 @@
-#[cfg(test)]
-pub struct Env {
-    pub username: String,
-    pub api_key: String,
-}
-
-#[cfg(test)]
-impl Env {
-    pub fn new() -> Self {
-        use dotenv::dotenv;
-        use std::env;
-
-        dotenv().ok();
-        Env {
-            username: env::var("DANBOORU_USERNAME").unwrap(),
-            api_key: env::var("DANBOORU_API_KEY").unwrap(),
-        }
-    }
-}